@@ -208,18 +208,22 @@ class PaillierMentalPoker {
   }
 
   static buildMerkleTree(encryptedDeck: bigint[]): Uint8Array {
-    let nodes = encryptedDeck.map(card => {
+    let nodes = encryptedDeck.map((card, index) => {
       const bytes = this.bigIntToBytes(card);
-      return keccak_256(bytes);
+      const leaf = new Uint8Array(34);
+      leaf.set([0x00, index], 0);
+      leaf.set(keccak_256(bytes), 2);
+      return keccak_256(leaf);
     });
 
     while (nodes.length > 1) {
       const nextLevel: Uint8Array[] = [];
       for (let i = 0; i < nodes.length; i += 2) {
         if (i + 1 < nodes.length) {
-          const combined = new Uint8Array(64);
-          combined.set(nodes[i], 0);
-          combined.set(nodes[i + 1], 32);
+          const combined = new Uint8Array(65);
+          combined[0] = 0x01;
+          combined.set(nodes[i], 1);
+          combined.set(nodes[i + 1], 33);
           nextLevel.push(keccak_256(combined));
         } else {
           nextLevel.push(nodes[i]);
@@ -16,6 +16,20 @@ import * as paillierBigint from 'paillier-bigint';
 import { keccak_256 } from "@noble/hashes/sha3";
 import crypto from 'crypto';
 
+/**
+ * circomlibjs Poseidon, as returned by `await buildPoseidon()`
+ * `poseidon(inputs, capacity)` returns an element of its field `F`
+ */
+interface CircomlibPoseidon {
+  (inputs: bigint[], initState?: bigint): unknown;
+  F: { toObject(element: unknown): bigint };
+}
+
+/**
+ * Merkle hash of a game: keccak_256 or sha256 from @noble/hashes, or circomlibjs Poseidon
+ */
+type MerkleHasher = ((data: Uint8Array) => Uint8Array) | CircomlibPoseidon;
+
 /**
  * Card utilities
  */
@@ -307,12 +321,24 @@ class PaillierMentalPoker {
 
   /**
    * Build Merkle tree from encrypted cards
+   * `hash` must match the game's MerkleHash: keccak_256 or sha256 from @noble/hashes,
+   * or `await buildPoseidon()` from circomlibjs for MerkleHash::Poseidon
    */
-  static buildMerkleTree(encryptedDeck: bigint[]): Uint8Array {
-    // Hash each encrypted card
-    let nodes = encryptedDeck.map(card => {
+  static buildMerkleTree(
+    encryptedDeck: bigint[],
+    hash: MerkleHasher = keccak_256
+  ): Uint8Array {
+    if ('F' in hash) {
+      return this.buildPoseidonMerkleTree(encryptedDeck, hash);
+    }
+
+    // Hash each encrypted card with its deck position: H(0x00 || index || H(card))
+    let nodes = encryptedDeck.map((card, index) => {
       const bytes = this.bigIntToBytes(card);
-      return keccak_256(bytes);
+      const leaf = new Uint8Array(34);
+      leaf.set([0x00, index], 0);
+      leaf.set(hash(bytes), 2);
+      return hash(leaf);
     });
 
     // Build tree bottom-up
@@ -321,10 +347,11 @@ class PaillierMentalPoker {
 
       for (let i = 0; i < nodes.length; i += 2) {
         if (i + 1 < nodes.length) {
-          const combined = new Uint8Array(64);
-          combined.set(nodes[i], 0);
-          combined.set(nodes[i + 1], 32);
-          nextLevel.push(keccak_256(combined));
+          const combined = new Uint8Array(65);
+          combined[0] = 0x01;
+          combined.set(nodes[i], 1);
+          combined.set(nodes[i + 1], 33);
+          nextLevel.push(hash(combined));
         } else {
           nextLevel.push(nodes[i]);
         }
@@ -336,6 +363,41 @@ class PaillierMentalPoker {
     return nodes[0];
   }
 
+  /**
+   * Build a Poseidon Merkle tree from encrypted cards
+   * Field elements replace the byte prefixes: each card's digest absorbs its bytes 31 at a
+   * time starting from their length, leaves are Poseidon with capacity 1 over
+   * (index, digest), and parents are Poseidon(left, right).
+   */
+  private static buildPoseidonMerkleTree(
+    encryptedDeck: bigint[],
+    poseidon: CircomlibPoseidon
+  ): Uint8Array {
+    const hash = (left: bigint, right: bigint, capacity = 0n): bigint =>
+      poseidon.F.toObject(poseidon([left, right], capacity));
+
+    let nodes = encryptedDeck.map((card, index) => {
+      const bytes = this.bigIntToBytes(card);
+      let digest = BigInt(bytes.length);
+      for (let i = 0; i < bytes.length; i += 31) {
+        const chunk = Buffer.from(bytes.subarray(i, i + 31)).toString('hex');
+        digest = hash(digest, BigInt('0x' + chunk));
+      }
+      return hash(BigInt(index), digest, 1n);
+    });
+
+    // Build tree bottom-up, promoting an odd last node
+    while (nodes.length > 1) {
+      const nextLevel: bigint[] = [];
+      for (let i = 0; i < nodes.length; i += 2) {
+        nextLevel.push(i + 1 < nodes.length ? hash(nodes[i], nodes[i + 1]) : nodes[i]);
+      }
+      nodes = nextLevel;
+    }
+
+    return this.bigIntToBytes(nodes[0]);
+  }
+
   /**
    * Reshuffle and re-encrypt deck (Player 2's operation)
    */
@@ -0,0 +1,395 @@
+//! Host-side conversion between snarkjs Groth16 JSON and the zkpoker on-chain encoding
+//!
+//! snarkjs writes BN254 points as decimal strings in projective form, with G2
+//! coordinates ordered `[c0, c1]`. The program expects the alt_bn128 syscall encoding
+//! (see `zkpoker::zk::groth16`):
+//! - G1 points are big-endian `x || y`
+//! - G2 points are big-endian `x_c1 || x_c0 || y_c1 || y_c0`
+//! - Proofs are `-A || B || C`, with A negated here rather than on-chain
+//! - The point at infinity is all zeros
+
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use zkpoker::state::ZkProof;
+use zkpoker::zk::groth16::{is_scalar_in_field, Groth16VerifyingKey, GROTH16_PROOF_LEN};
+
+/// BN254 base field modulus p, big-endian
+pub const BN254_BASE_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum SnarkjsError {
+    #[error("invalid snarkjs JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    
+    #[error("unsupported protocol {0:?}, expected groth16")]
+    UnsupportedProtocol(String),
+    
+    #[error("unsupported curve {0:?}, expected bn128")]
+    UnsupportedCurve(String),
+    
+    #[error("{0:?} is not a BN254 base field element")]
+    InvalidFieldElement(String),
+    
+    #[error("{0:?} is not a BN254 scalar field element")]
+    InvalidScalar(String),
+    
+    #[error("point is neither affine nor the point at infinity")]
+    NotAffine,
+    
+    #[error("nPublic is {n_public} but IC has {ic_len} points")]
+    PublicInputCountMismatch { n_public: usize, ic_len: usize },
+    
+    #[error("proof data must be {GROTH16_PROOF_LEN} bytes, got {0}")]
+    InvalidProofLength(usize),
+}
+
+pub type Result<T> = std::result::Result<T, SnarkjsError>;
+
+/// G1 point as written by snarkjs: `[x, y, z]`
+pub type JsonG1 = [String; 3];
+/// G2 point as written by snarkjs: `[[x_c0, x_c1], [y_c0, y_c1], [z_c0, z_c1]]`
+pub type JsonG2 = [[String; 2]; 3];
+
+/// snarkjs `proof.json`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnarkjsProof {
+    pub pi_a: JsonG1,
+    pub pi_b: JsonG2,
+    pub pi_c: JsonG1,
+    pub protocol: String,
+    pub curve: String,
+}
+
+/// snarkjs `verification_key.json`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnarkjsVerifyingKey {
+    pub protocol: String,
+    pub curve: String,
+    #[serde(rename = "nPublic")]
+    pub n_public: usize,
+    pub vk_alpha_1: JsonG1,
+    pub vk_beta_2: JsonG2,
+    pub vk_gamma_2: JsonG2,
+    pub vk_delta_2: JsonG2,
+    /// e(alpha, beta), precomputed by snarkjs and unused on-chain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vk_alphabeta_12: Option<serde_json::Value>,
+    #[serde(rename = "IC")]
+    pub ic: Vec<JsonG1>,
+}
+
+/// Verifying key in the registry's layout, matching the `publish_verifying_key` arguments
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey {
+    pub alpha_g1: [u8; 64],
+    pub beta_g2: [u8; 128],
+    pub gamma_g2: [u8; 128],
+    pub delta_g2: [u8; 128],
+    pub ic: Vec<[u8; 64]>,
+}
+
+impl VerifyingKey {
+    /// Borrow in the form the on-chain verifier takes
+    pub fn as_groth16(&self) -> Groth16VerifyingKey<'_> {
+        Groth16VerifyingKey {
+            alpha_g1: self.alpha_g1,
+            beta_g2: self.beta_g2,
+            gamma_g2: self.gamma_g2,
+            delta_g2: self.delta_g2,
+            ic: &self.ic,
+        }
+    }
+}
+
+impl SnarkjsProof {
+    pub fn from_json(json: &str) -> Result<Self> {
+        let proof: Self = serde_json::from_str(json)?;
+        check_header(&proof.protocol, &proof.curve)?;
+        Ok(proof)
+    }
+    
+    /// Encode as `ZkProof::proof_data`: `-A || B || C`
+    pub fn to_proof_data(&self) -> Result<Vec<u8>> {
+        check_header(&self.protocol, &self.curve)?;
+        let mut data = Vec::with_capacity(GROTH16_PROOF_LEN);
+        data.extend_from_slice(&negate_g1(&encode_g1(&self.pi_a)?));
+        data.extend_from_slice(&encode_g2(&self.pi_b)?);
+        data.extend_from_slice(&encode_g1(&self.pi_c)?);
+        Ok(data)
+    }
+    
+    pub fn to_zk_proof(&self) -> Result<ZkProof> {
+        Ok(ZkProof { proof_data: self.to_proof_data()? })
+    }
+    
+    /// Decode `ZkProof::proof_data`, undoing the negation of A
+    pub fn from_proof_data(data: &[u8]) -> Result<Self> {
+        if data.len() != GROTH16_PROOF_LEN {
+            return Err(SnarkjsError::InvalidProofLength(data.len()));
+        }
+        let a_neg: [u8; 64] = data[..64].try_into().unwrap();
+        Ok(SnarkjsProof {
+            pi_a: decode_g1(&negate_g1(&a_neg)),
+            pi_b: decode_g2(data[64..192].try_into().unwrap()),
+            pi_c: decode_g1(data[192..].try_into().unwrap()),
+            protocol: "groth16".to_string(),
+            curve: "bn128".to_string(),
+        })
+    }
+}
+
+impl SnarkjsVerifyingKey {
+    pub fn from_json(json: &str) -> Result<Self> {
+        let vk: Self = serde_json::from_str(json)?;
+        check_header(&vk.protocol, &vk.curve)?;
+        Ok(vk)
+    }
+    
+    /// Encode in the registry's layout
+    pub fn to_verifying_key(&self) -> Result<VerifyingKey> {
+        check_header(&self.protocol, &self.curve)?;
+        if self.ic.len() != self.n_public + 1 {
+            return Err(SnarkjsError::PublicInputCountMismatch {
+                n_public: self.n_public,
+                ic_len: self.ic.len(),
+            });
+        }
+        Ok(VerifyingKey {
+            alpha_g1: encode_g1(&self.vk_alpha_1)?,
+            beta_g2: encode_g2(&self.vk_beta_2)?,
+            gamma_g2: encode_g2(&self.vk_gamma_2)?,
+            delta_g2: encode_g2(&self.vk_delta_2)?,
+            ic: self.ic.iter().map(encode_g1).collect::<Result<_>>()?,
+        })
+    }
+    
+    /// Decode a registry key; `vk_alphabeta_12` is left out
+    pub fn from_verifying_key(vk: &VerifyingKey) -> Self {
+        SnarkjsVerifyingKey {
+            protocol: "groth16".to_string(),
+            curve: "bn128".to_string(),
+            n_public: vk.ic.len().saturating_sub(1),
+            vk_alpha_1: decode_g1(&vk.alpha_g1),
+            vk_beta_2: decode_g2(&vk.beta_g2),
+            vk_gamma_2: decode_g2(&vk.gamma_g2),
+            vk_delta_2: decode_g2(&vk.delta_g2),
+            vk_alphabeta_12: None,
+            ic: vk.ic.iter().map(decode_g1).collect(),
+        }
+    }
+}
+
+/// Parse snarkjs `public.json` into 32-byte big-endian public inputs
+pub fn public_inputs_from_json(json: &str) -> Result<Vec<[u8; 32]>> {
+    let signals: Vec<String> = serde_json::from_str(json)?;
+    signals
+        .iter()
+        .map(|s| {
+            let scalar = parse_decimal(s).ok_or_else(|| SnarkjsError::InvalidScalar(s.clone()))?;
+            if !is_scalar_in_field(&scalar) {
+                return Err(SnarkjsError::InvalidScalar(s.clone()));
+            }
+            Ok(scalar)
+        })
+        .collect()
+}
+
+fn check_header(protocol: &str, curve: &str) -> Result<()> {
+    if protocol != "groth16" {
+        return Err(SnarkjsError::UnsupportedProtocol(protocol.to_string()));
+    }
+    if curve != "bn128" {
+        return Err(SnarkjsError::UnsupportedCurve(curve.to_string()));
+    }
+    Ok(())
+}
+
+/// Parse a decimal string into 32 big-endian bytes
+fn parse_decimal(s: &str) -> Option<[u8; 32]> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let bytes = BigUint::parse_bytes(s.as_bytes(), 10)?.to_bytes_be();
+    if bytes.len() > 32 {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    out[32 - bytes.len()..].copy_from_slice(&bytes);
+    Some(out)
+}
+
+fn parse_base_field(s: &str) -> Result<[u8; 32]> {
+    match parse_decimal(s) {
+        Some(x) if x < BN254_BASE_MODULUS => Ok(x),
+        _ => Err(SnarkjsError::InvalidFieldElement(s.to_string())),
+    }
+}
+
+fn to_decimal(bytes: &[u8]) -> String {
+    BigUint::from_bytes_be(bytes).to_string()
+}
+
+fn encode_g1(point: &JsonG1) -> Result<[u8; 64]> {
+    let [x, y, z] = point;
+    let mut out = [0u8; 64];
+    match parse_base_field(z)? {
+        z if z == [0u8; 32] => return Ok(out),
+        z if z != one() => return Err(SnarkjsError::NotAffine),
+        _ => {}
+    }
+    out[..32].copy_from_slice(&parse_base_field(x)?);
+    out[32..].copy_from_slice(&parse_base_field(y)?);
+    Ok(out)
+}
+
+fn encode_g2(point: &JsonG2) -> Result<[u8; 128]> {
+    let [x, y, z] = point;
+    let mut out = [0u8; 128];
+    match (parse_base_field(&z[0])?, parse_base_field(&z[1])?) {
+        (z0, z1) if z0 == [0u8; 32] && z1 == [0u8; 32] => return Ok(out),
+        (z0, z1) if z0 != one() || z1 != [0u8; 32] => return Err(SnarkjsError::NotAffine),
+        _ => {}
+    }
+    // The syscalls take the c1 coefficient first
+    out[..32].copy_from_slice(&parse_base_field(&x[1])?);
+    out[32..64].copy_from_slice(&parse_base_field(&x[0])?);
+    out[64..96].copy_from_slice(&parse_base_field(&y[1])?);
+    out[96..].copy_from_slice(&parse_base_field(&y[0])?);
+    Ok(out)
+}
+
+fn decode_g1(point: &[u8; 64]) -> JsonG1 {
+    if point.iter().all(|b| *b == 0) {
+        return ["0".to_string(), "1".to_string(), "0".to_string()];
+    }
+    [to_decimal(&point[..32]), to_decimal(&point[32..]), "1".to_string()]
+}
+
+fn decode_g2(point: &[u8; 128]) -> JsonG2 {
+    if point.iter().all(|b| *b == 0) {
+        return [
+            ["0".to_string(), "0".to_string()],
+            ["1".to_string(), "0".to_string()],
+            ["0".to_string(), "0".to_string()],
+        ];
+    }
+    [
+        [to_decimal(&point[32..64]), to_decimal(&point[..32])],
+        [to_decimal(&point[96..]), to_decimal(&point[64..96])],
+        ["1".to_string(), "0".to_string()],
+    ]
+}
+
+/// -(x, y) = (x, p - y); the point at infinity is its own negation
+fn negate_g1(point: &[u8; 64]) -> [u8; 64] {
+    let y = BigUint::from_bytes_be(&point[32..]);
+    if y == BigUint::default() {
+        return *point;
+    }
+    let neg_y = (BigUint::from_bytes_be(&BN254_BASE_MODULUS) - y).to_bytes_be();
+    let mut out = [0u8; 64];
+    out[..32].copy_from_slice(&point[..32]);
+    out[64 - neg_y.len()..].copy_from_slice(&neg_y);
+    out
+}
+
+fn one() -> [u8; 32] {
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    one
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zkpoker::zk::groth16::{verify_groth16, Groth16Proof};
+    
+    // Multiplier circuit c = a * b with public signals (c, a) = (33, 3)
+    const PROOF_JSON: &str = include_str!("../fixtures/proof.json");
+    const VK_JSON: &str = include_str!("../fixtures/verification_key.json");
+    const PUBLIC_JSON: &str = include_str!("../fixtures/public.json");
+    
+    #[test]
+    fn test_fixture_proof_verifies_on_program_verifier() {
+        let proof_data = SnarkjsProof::from_json(PROOF_JSON).unwrap().to_proof_data().unwrap();
+        let vk = SnarkjsVerifyingKey::from_json(VK_JSON).unwrap().to_verifying_key().unwrap();
+        let inputs = public_inputs_from_json(PUBLIC_JSON).unwrap();
+        
+        assert_eq!(proof_data.len(), GROTH16_PROOF_LEN);
+        let proof = Groth16Proof::from_bytes(&proof_data).unwrap();
+        assert!(verify_groth16(&vk.as_groth16(), &proof, &inputs).is_ok());
+        
+        // Wrong public inputs, or A left un-negated, don't verify
+        let mut wrong_inputs = inputs.clone();
+        wrong_inputs[0][31] ^= 1;
+        assert!(verify_groth16(&vk.as_groth16(), &proof, &wrong_inputs).is_err());
+        let mut raw_a = proof_data.clone();
+        raw_a[..64].copy_from_slice(&negate_g1(proof_data[..64].try_into().unwrap()));
+        let raw_a = Groth16Proof::from_bytes(&raw_a).unwrap();
+        assert!(verify_groth16(&vk.as_groth16(), &raw_a, &inputs).is_err());
+    }
+    
+    #[test]
+    fn test_fixtures_round_trip() {
+        let proof = SnarkjsProof::from_json(PROOF_JSON).unwrap();
+        let proof_data = proof.to_proof_data().unwrap();
+        assert_eq!(SnarkjsProof::from_proof_data(&proof_data).unwrap(), proof);
+        
+        let vk_json = SnarkjsVerifyingKey::from_json(VK_JSON).unwrap();
+        let vk = vk_json.to_verifying_key().unwrap();
+        let decoded = SnarkjsVerifyingKey::from_verifying_key(&vk);
+        assert_eq!(decoded, SnarkjsVerifyingKey { vk_alphabeta_12: None, ..vk_json });
+        assert_eq!(decoded.to_verifying_key().unwrap(), vk);
+        
+        // Re-serialized JSON parses back to the same encoding
+        let reparsed = SnarkjsProof::from_json(&serde_json::to_string(&proof).unwrap()).unwrap();
+        assert_eq!(reparsed.to_proof_data().unwrap(), proof_data);
+    }
+    
+    #[test]
+    fn test_infinity_and_negation() {
+        let infinity = decode_g1(&[0u8; 64]);
+        assert_eq!(encode_g1(&infinity).unwrap(), [0u8; 64]);
+        assert_eq!(negate_g1(&[0u8; 64]), [0u8; 64]);
+        assert_eq!(encode_g2(&decode_g2(&[0u8; 128])).unwrap(), [0u8; 128]);
+        
+        let vk = SnarkjsVerifyingKey::from_json(VK_JSON).unwrap().to_verifying_key().unwrap();
+        assert_ne!(negate_g1(&vk.alpha_g1), vk.alpha_g1);
+        assert_eq!(negate_g1(&negate_g1(&vk.alpha_g1)), vk.alpha_g1);
+    }
+    
+    #[test]
+    fn test_rejects_malformed_input() {
+        let proof = SnarkjsProof::from_json(PROOF_JSON).unwrap();
+        
+        let mut wrong_curve = proof.clone();
+        wrong_curve.curve = "bls12381".to_string();
+        assert!(matches!(wrong_curve.to_proof_data(), Err(SnarkjsError::UnsupportedCurve(_))));
+        
+        let mut projective = proof.clone();
+        projective.pi_a[2] = "2".to_string();
+        assert!(matches!(projective.to_proof_data(), Err(SnarkjsError::NotAffine)));
+        
+        let mut out_of_field = proof.clone();
+        out_of_field.pi_c[0] = to_decimal(&BN254_BASE_MODULUS);
+        assert!(matches!(out_of_field.to_proof_data(), Err(SnarkjsError::InvalidFieldElement(_))));
+        
+        let mut not_decimal = proof;
+        not_decimal.pi_b[0][1] = "0x1".to_string();
+        assert!(not_decimal.to_proof_data().is_err());
+        
+        let mut vk = SnarkjsVerifyingKey::from_json(VK_JSON).unwrap();
+        vk.n_public += 1;
+        assert!(matches!(
+            vk.to_verifying_key(),
+            Err(SnarkjsError::PublicInputCountMismatch { .. })
+        ));
+        
+        assert!(SnarkjsProof::from_proof_data(&[0u8; GROTH16_PROOF_LEN - 1]).is_err());
+        assert!(public_inputs_from_json(r#"["-1"]"#).is_err());
+        assert!(public_inputs_from_json(&format!("[\"{}\"]", to_decimal(&BN254_BASE_MODULUS))).is_err());
+    }
+}
@@ -0,0 +1,324 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crypto_bigint::modular::constant_mod::Residue;
+use crypto_bigint::{impl_modulus, Encoding, U256};
+use solana_zk_token_sdk::curve25519::ristretto::{
+    add_ristretto, multiply_ristretto, subtract_ristretto, validate_ristretto, PodRistrettoPoint,
+};
+use solana_zk_token_sdk::curve25519::scalar::PodScalar;
+use crate::errors::PokerError;
+use crate::state::{DECK_SIZE, ELGAMAL_CIPHERTEXT_LEN, ELGAMAL_POINT_LEN};
+
+// Elliptic-curve ElGamal over Ristretto255, the compact alternative to Paillier
+//
+// Cards are encoded as M = (card + 1)·G and encrypted under the joint key P = P_0 + P_1 of
+// both players: (c1, c2) = (r·G, M + r·P). Reshuffling re-randomises each ciphertext under
+// the same key, and a card is revealed by combining one decryption share s_i·c1 per player:
+// M = c2 - s_0·c1 - s_1·c1. Points are 32-byte compressed encodings and scalars are
+// canonical little-endian; the group operations run on the curve25519 syscalls on-chain
+// and on curve25519-dalek on the host.
+//
+// Decryption shares come with a Chaum–Pedersen proof that log_G(P_i) = log_c1(D_i), made
+// non-interactive with Fiat–Shamir. Proofs are sent as (challenge, response), and the
+// verifier recomputes the commitments A = z·G - e·P_i and B = z·c1 - e·D_i. Challenges
+// are truncated to 252 bits so they are always canonical scalars.
+
+/// Ristretto255 generator G
+pub const RISTRETTO_BASEPOINT: PodRistrettoPoint = PodRistrettoPoint([
+    0xe2, 0xf2, 0xae, 0x0a, 0x6a, 0xbc, 0x4e, 0x71, 0xa8, 0x84, 0xa9, 0x61, 0xc5, 0x00, 0x51, 0x5f,
+    0x58, 0xe3, 0x0b, 0x6a, 0xa5, 0x82, 0xdd, 0x8d, 0xb6, 0xa6, 0x59, 0x45, 0xe0, 0x8d, 0x2d, 0x76,
+]);
+
+/// Encoding of the identity element
+const IDENTITY: PodRistrettoPoint = PodRistrettoPoint([0u8; 32]);
+
+/// Fiat–Shamir domain for decryption share proofs
+const SHARE_PROOF_DOMAIN: &[u8] = b"zkpoker:chaum_pedersen:v1";
+
+impl_modulus!(
+    Ristretto255Order,
+    U256,
+    "1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed"
+);
+
+/// Scalar modulo the Ristretto255 group order, used by the prover
+type OrderResidue = Residue<Ristretto255Order, { U256::LIMBS }>;
+
+/// An ElGamal ciphertext (c1, c2) = (r·G, M + r·P)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ElGamalCiphertext {
+    pub c1: PodRistrettoPoint,
+    pub c2: PodRistrettoPoint,
+}
+
+impl ElGamalCiphertext {
+    /// Parse `c1 || c2`, checking both halves are valid points
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        require!(bytes.len() == ELGAMAL_CIPHERTEXT_LEN, PokerError::CiphertextWrongLength);
+        Ok(ElGamalCiphertext {
+            c1: parse_point(&bytes[..ELGAMAL_POINT_LEN])?,
+            c2: parse_point(&bytes[ELGAMAL_POINT_LEN..])?,
+        })
+    }
+    
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.c1.0, self.c2.0].concat()
+    }
+}
+
+/// Parse a compressed Ristretto255 point
+pub fn parse_point(bytes: &[u8]) -> Result<PodRistrettoPoint> {
+    let point = PodRistrettoPoint(bytes.try_into().map_err(|_| PokerError::InvalidCurvePoint)?);
+    require!(validate_ristretto(&point), PokerError::InvalidCurvePoint);
+    Ok(point)
+}
+
+/// Check a player's public key: a valid point other than the identity
+pub fn validate_public_key(public_key: &[u8; 32]) -> Result<()> {
+    let point = parse_point(public_key)?;
+    require!(point != IDENTITY, PokerError::InvalidCurvePoint);
+    Ok(())
+}
+
+fn add(lhs: &PodRistrettoPoint, rhs: &PodRistrettoPoint) -> Result<PodRistrettoPoint> {
+    Ok(add_ristretto(lhs, rhs).ok_or(PokerError::InvalidCurvePoint)?)
+}
+
+fn subtract(lhs: &PodRistrettoPoint, rhs: &PodRistrettoPoint) -> Result<PodRistrettoPoint> {
+    Ok(subtract_ristretto(lhs, rhs).ok_or(PokerError::InvalidCurvePoint)?)
+}
+
+fn multiply(scalar: &PodScalar, point: &PodRistrettoPoint) -> Result<PodRistrettoPoint> {
+    Ok(multiply_ristretto(scalar, point).ok_or(PokerError::InvalidCurveScalar)?)
+}
+
+/// Scalar from a small integer
+fn small_scalar(value: u8) -> PodScalar {
+    let mut bytes = [0u8; 32];
+    bytes[0] = value;
+    PodScalar(bytes)
+}
+
+/// Public key s·G for a secret scalar
+pub fn public_key(secret: &PodScalar) -> Result<PodRistrettoPoint> {
+    multiply(secret, &RISTRETTO_BASEPOINT)
+}
+
+/// Joint key the deck is encrypted under
+pub fn joint_key(keys: &[[u8; 32]; 2]) -> Result<PodRistrettoPoint> {
+    add(&parse_point(&keys[0])?, &parse_point(&keys[1])?)
+}
+
+/// Plaintext point for a card
+pub fn encode_card(card: u8) -> Result<PodRistrettoPoint> {
+    require!(card < DECK_SIZE, PokerError::InvalidPlaintext);
+    multiply(&small_scalar(card + 1), &RISTRETTO_BASEPOINT)
+}
+
+/// Card encoded by a plaintext point, if any
+/// At most 52 additions, so cheap enough to run on-chain.
+pub fn decode_card(point: &PodRistrettoPoint) -> Option<u8> {
+    let mut candidate = RISTRETTO_BASEPOINT;
+    for card in 0..DECK_SIZE {
+        if candidate == *point {
+            return Some(card);
+        }
+        candidate = add_ristretto(&candidate, &RISTRETTO_BASEPOINT)?;
+    }
+    None
+}
+
+/// Encrypt a card under the joint key with randomness r
+pub fn encrypt(
+    joint_key: &PodRistrettoPoint,
+    card: u8,
+    randomness: &PodScalar,
+) -> Result<ElGamalCiphertext> {
+    Ok(ElGamalCiphertext {
+        c1: public_key(randomness)?,
+        c2: add(&encode_card(card)?, &multiply(randomness, joint_key)?)?,
+    })
+}
+
+/// Re-randomise a ciphertext: (c1 + r·G, c2 + r·P)
+pub fn rerandomize(
+    joint_key: &PodRistrettoPoint,
+    ciphertext: &ElGamalCiphertext,
+    randomness: &PodScalar,
+) -> Result<ElGamalCiphertext> {
+    Ok(ElGamalCiphertext {
+        c1: add(&ciphertext.c1, &public_key(randomness)?)?,
+        c2: add(&ciphertext.c2, &multiply(randomness, joint_key)?)?,
+    })
+}
+
+/// A player's decryption share s·c1
+pub fn decryption_share(secret: &PodScalar, ciphertext: &ElGamalCiphertext) -> Result<PodRistrettoPoint> {
+    multiply(secret, &ciphertext.c1)
+}
+
+/// Remove every player's share from c2, leaving the plaintext point
+pub fn combine_shares(
+    ciphertext: &ElGamalCiphertext,
+    shares: &[PodRistrettoPoint],
+) -> Result<PodRistrettoPoint> {
+    shares.iter().try_fold(ciphertext.c2, |point, share| subtract(&point, share))
+}
+
+/// Fiat–Shamir challenge for a decryption share proof
+fn share_challenge(
+    public_key: &PodRistrettoPoint,
+    ciphertext: &ElGamalCiphertext,
+    share: &PodRistrettoPoint,
+    commitment_g: &PodRistrettoPoint,
+    commitment_c1: &PodRistrettoPoint,
+) -> PodScalar {
+    let mut challenge = hashv(&[
+        SHARE_PROOF_DOMAIN,
+        &public_key.0,
+        &ciphertext.c1.0,
+        &ciphertext.c2.0,
+        &share.0,
+        &commitment_g.0,
+        &commitment_c1.0,
+    ])
+    .to_bytes();
+    challenge[31] &= 0x0f;
+    PodScalar(challenge)
+}
+
+/// Decryption share with a Chaum–Pedersen proof, as (share, challenge, response)
+/// Host-side: `nonce` must be a fresh uniformly random canonical scalar for every proof.
+pub fn prove_decryption_share(
+    secret: &PodScalar,
+    ciphertext: &ElGamalCiphertext,
+    nonce: &PodScalar,
+) -> Result<(PodRistrettoPoint, PodScalar, PodScalar)> {
+    let public_key = public_key(secret)?;
+    let share = decryption_share(secret, ciphertext)?;
+    let challenge = share_challenge(
+        &public_key,
+        ciphertext,
+        &share,
+        &multiply(nonce, &RISTRETTO_BASEPOINT)?,
+        &multiply(nonce, &ciphertext.c1)?,
+    );
+    
+    let to_residue = |scalar: &PodScalar| OrderResidue::new(&U256::from_le_bytes(scalar.0));
+    let response = to_residue(nonce).add(&to_residue(&challenge).mul(&to_residue(secret)));
+    Ok((share, challenge, PodScalar(response.retrieve().to_le_bytes())))
+}
+
+/// Check a decryption share D = s·c1 against the player's public key P = s·G
+pub fn verify_decryption_share(
+    public_key: &PodRistrettoPoint,
+    ciphertext: &ElGamalCiphertext,
+    share: &PodRistrettoPoint,
+    challenge: &PodScalar,
+    response: &PodScalar,
+) -> Result<()> {
+    require!(challenge.0[31] & 0xf0 == 0, PokerError::InvalidDecryptionProof);
+    let commitment_g = subtract(
+        &multiply(response, &RISTRETTO_BASEPOINT)?,
+        &multiply(challenge, public_key)?,
+    )?;
+    let commitment_c1 = subtract(&multiply(response, &ciphertext.c1)?, &multiply(challenge, share)?)?;
+    require!(
+        share_challenge(public_key, ciphertext, share, &commitment_g, &commitment_c1) == *challenge,
+        PokerError::InvalidDecryptionProof
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn scalar(value: u64) -> PodScalar {
+        let mut bytes = [0u8; 32];
+        bytes[..8].copy_from_slice(&value.to_le_bytes());
+        PodScalar(bytes)
+    }
+    
+    fn keys() -> ([PodScalar; 2], [[u8; 32]; 2]) {
+        let secrets = [scalar(0x1234_5678), scalar(0x9abc_def0)];
+        let public = [public_key(&secrets[0]).unwrap().0, public_key(&secrets[1]).unwrap().0];
+        (secrets, public)
+    }
+    
+    #[test]
+    fn test_encrypt_reshuffle_decrypt_round_trip() {
+        let (secrets, public) = keys();
+        let joint = joint_key(&public).unwrap();
+        
+        for card in [0, 17, DECK_SIZE - 1] {
+            let ciphertext = encrypt(&joint, card, &scalar(1000 + card as u64)).unwrap();
+            let reshuffled = rerandomize(&joint, &ciphertext, &scalar(77)).unwrap();
+            assert_ne!(reshuffled, ciphertext);
+            assert_eq!(ElGamalCiphertext::from_bytes(&reshuffled.to_bytes()).unwrap(), reshuffled);
+            
+            let shares: Vec<PodRistrettoPoint> = secrets
+                .iter()
+                .map(|secret| decryption_share(secret, &reshuffled).unwrap())
+                .collect();
+            assert_eq!(decode_card(&combine_shares(&reshuffled, &shares).unwrap()), Some(card));
+            
+            // One share alone reveals nothing
+            assert_eq!(decode_card(&combine_shares(&reshuffled, &shares[..1]).unwrap()), None);
+        }
+    }
+    
+    #[test]
+    fn test_card_encoding() {
+        assert_eq!(encode_card(0).unwrap(), RISTRETTO_BASEPOINT);
+        assert_eq!(
+            encode_card(1).unwrap(),
+            add(&RISTRETTO_BASEPOINT, &RISTRETTO_BASEPOINT).unwrap()
+        );
+        for card in 0..DECK_SIZE {
+            assert_eq!(decode_card(&encode_card(card).unwrap()), Some(card));
+        }
+        assert!(encode_card(DECK_SIZE).is_err());
+    }
+    
+    #[test]
+    fn test_rejects_malformed_inputs() {
+        let (_, public) = keys();
+        assert!(validate_public_key(&public[0]).is_ok());
+        assert!(validate_public_key(&[0u8; 32]).is_err());
+        assert!(validate_public_key(&[0xff; 32]).is_err());
+        
+        assert!(ElGamalCiphertext::from_bytes(&[0u8; 63]).is_err());
+        let mut bytes = [RISTRETTO_BASEPOINT.0, RISTRETTO_BASEPOINT.0].concat();
+        assert!(ElGamalCiphertext::from_bytes(&bytes).is_ok());
+        bytes[40] ^= 1;
+        assert!(ElGamalCiphertext::from_bytes(&bytes).is_err());
+        
+        // Scalars must be reduced modulo the group order
+        assert!(public_key(&PodScalar([0xff; 32])).is_err());
+    }
+    
+    #[test]
+    fn test_decryption_share_proofs() {
+        let (secrets, public) = keys();
+        let joint = joint_key(&public).unwrap();
+        let ciphertext = encrypt(&joint, 30, &scalar(555)).unwrap();
+        let dealer_key = parse_point(&public[1]).unwrap();
+        
+        let (share, challenge, response) =
+            prove_decryption_share(&secrets[1], &ciphertext, &scalar(0xdead_beef)).unwrap();
+        assert_eq!(share, decryption_share(&secrets[1], &ciphertext).unwrap());
+        assert!(verify_decryption_share(&dealer_key, &ciphertext, &share, &challenge, &response).is_ok());
+        
+        // A share made with another secret, or bound to another key or ciphertext, fails
+        let wrong_share = decryption_share(&secrets[0], &ciphertext).unwrap();
+        assert!(verify_decryption_share(&dealer_key, &ciphertext, &wrong_share, &challenge, &response).is_err());
+        let other_key = parse_point(&public[0]).unwrap();
+        assert!(verify_decryption_share(&other_key, &ciphertext, &share, &challenge, &response).is_err());
+        let other = rerandomize(&joint, &ciphertext, &scalar(3)).unwrap();
+        assert!(verify_decryption_share(&dealer_key, &other, &share, &challenge, &response).is_err());
+        let mut tampered = response;
+        tampered.0[0] ^= 1;
+        assert!(verify_decryption_share(&dealer_key, &ciphertext, &share, &challenge, &tampered).is_err());
+    }
+}
@@ -0,0 +1,657 @@
+// Heads-up betting rules, independent of Anchor
+//
+// `HandEngine::apply` takes a betting state, the acting seat, an action and the current
+// time, and returns the next state or an `EngineError`. It only uses `core`, so the same
+// rules run in the program, in bots and simulators, and in plain `cargo test`. The
+// program converts `Game` into a `BettingState` and back with `Game::betting_state` and
+// `Game::apply_betting_state`.
+//
+// Chips are conserved: every transition keeps `stacks[0] + stacks[1] + pot` unchanged.
+// Bets are never capped when they are made; when a street ends, the part of a bet the
+// opponent couldn't match is returned, so the bets going into the next street or the
+// showdown are always equal.
+
+/// Betting street
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Street {
+    PreFlop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    /// Street after this one; the river is the last
+    pub fn next(self) -> Option<Street> {
+        match self {
+            Street::PreFlop => Some(Street::Flop),
+            Street::Flop => Some(Street::Turn),
+            Street::Turn => Some(Street::River),
+            Street::River => None,
+        }
+    }
+}
+
+/// Player action, with raises sized as the amount put in above a call
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Raise(u64),
+    AllIn,
+}
+
+/// Betting state of a hand
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BettingState {
+    /// Current street
+    pub street: Street,
+    /// Whether the street is open for actions (cards are not being dealt or revealed)
+    pub open: bool,
+    pub dealer: u8,
+    /// Seat whose turn it is
+    pub to_act: u8,
+    pub stacks: [u64; 2],
+    /// Chips put in by each seat on the current street
+    pub bets: [u64; 2],
+    pub pot: u64,
+    /// Size of the last full bet or raise on this street, the minimum for the next raise
+    pub last_raise_size: u64,
+    /// Seat that made the last full bet or raise on this street
+    pub last_aggressor: Option<u8>,
+    pub folded: [bool; 2],
+    pub all_in: [bool; 2],
+    /// Whether each seat has acted on this street; posting a blind doesn't count
+    pub has_acted: [bool; 2],
+    /// Set when a fold ends the hand
+    pub winner: Option<u8>,
+    pub last_action_at: i64,
+    pub action_deadline: i64,
+}
+
+impl BettingState {
+    pub fn non_dealer(&self) -> u8 {
+        1 - self.dealer
+    }
+    
+    /// Chips held by both seats and the pot
+    pub fn total_chips(&self) -> u64 {
+        self.stacks[0] + self.stacks[1] + self.pot
+    }
+    
+    /// Whether a seat still has a decision to make on this street: it hasn't acted yet,
+    /// or it hasn't matched the opponent's bet. Nothing is left to decide once a seat
+    /// is all-in, or has matched an all-in opponent.
+    pub fn needs_to_act(&self, seat: u8) -> bool {
+        let player = seat as usize;
+        let opponent = 1 - player;
+        if self.folded[0] || self.folded[1] || self.all_in[player] {
+            return false;
+        }
+        if self.bets[player] < self.bets[opponent] {
+            return true;
+        }
+        !self.all_in[opponent] && !self.has_acted[player]
+    }
+    
+    /// Whether no further action is needed on this street: both seats have acted and
+    /// the bets are matched, so the big blind gets its option after a limp
+    pub fn is_round_complete(&self) -> bool {
+        !self.needs_to_act(0) && !self.needs_to_act(1)
+    }
+    
+    /// Whether the street is over with a player all-in, so nothing is left to bet
+    /// and the rest of the board can be dealt at once
+    pub fn is_all_in_runout(&self) -> bool {
+        self.winner.is_none()
+            && !self.folded[0]
+            && !self.folded[1]
+            && (self.all_in[0] || self.all_in[1])
+            && self.is_round_complete()
+    }
+    
+    /// Move chips from a seat's stack into its bet and the pot
+    fn put_in(&mut self, player: usize, amount: u64) {
+        self.bets[player] += amount;
+        self.stacks[player] -= amount;
+        self.pot += amount;
+        self.all_in[player] = self.stacks[player] == 0;
+    }
+    
+    /// Return the part of a bet the opponent hasn't matched. Once the street is over
+    /// only an all-in opponent can be short, so this is the uncalled part of a bet or
+    /// all-in against a smaller stack.
+    fn return_uncalled_bet(&mut self) {
+        let (high, low) = if self.bets[0] > self.bets[1] { (0, 1) } else { (1, 0) };
+        let uncalled = self.bets[high] - self.bets[low];
+        if uncalled == 0 {
+            return;
+        }
+        self.bets[high] -= uncalled;
+        self.stacks[high] += uncalled;
+        self.pot -= uncalled;
+        self.all_in[high] = false;
+    }
+    
+    /// Open the next street: bets are reset and, in heads-up, the big blind acts first.
+    /// The river stays the river.
+    pub fn next_street(&self) -> BettingState {
+        BettingState {
+            street: self.street.next().unwrap_or(Street::River),
+            open: true,
+            to_act: self.non_dealer(),
+            bets: [0, 0],
+            last_raise_size: 0,
+            last_aggressor: None,
+            has_acted: [false, false],
+            ..*self
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EngineError {
+    /// The hand is not in a betting round
+    NotBettingRound,
+    NotYourTurn,
+    /// The seat has folded
+    Folded,
+    /// The seat is all-in and can't act
+    AllIn,
+    InvalidAction,
+    InvalidBetAmount,
+    InsufficientFunds,
+    MinimumRaiseNotMet,
+    /// A short all-in raise doesn't let the last raiser raise again
+    BettingNotReopened,
+    /// Both seats have acted and the street is waiting to be advanced
+    RoundComplete,
+    InvalidPlayerIndex,
+    /// A seat can't cover its blind
+    InsufficientStack,
+}
+
+/// Betting rules for one game: blinds and the action timeout
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HandEngine {
+    pub small_blind: u64,
+    pub big_blind: u64,
+    /// Seconds a player has to act
+    pub action_timeout: i64,
+}
+
+impl HandEngine {
+    /// Post the blinds: in heads-up the dealer posts the small blind and the
+    /// non-dealer the big blind
+    pub fn post_blinds(&self, state: &BettingState) -> Result<BettingState, EngineError> {
+        let dealer = state.dealer as usize;
+        let non_dealer = state.non_dealer() as usize;
+        if state.stacks[dealer] < self.small_blind || state.stacks[non_dealer] < self.big_blind {
+            return Err(EngineError::InsufficientStack);
+        }
+        
+        let mut next = *state;
+        next.pot = 0;
+        next.bets = [0, 0];
+        next.put_in(dealer, self.small_blind);
+        next.put_in(non_dealer, self.big_blind);
+        next.has_acted = [false, false];
+        
+        // The big blind is the initial minimum raise, but posting it isn't a raise:
+        // the big blind keeps the option to raise a call
+        next.last_raise_size = self.big_blind;
+        next.last_aggressor = None;
+        
+        // A small blind that is all-in ends the betting and only calls that much of the big blind
+        if next.is_round_complete() {
+            next.return_uncalled_bet();
+        }
+        Ok(next)
+    }
+    
+    /// Apply `actor`'s action at time `now`
+    pub fn apply(
+        &self,
+        state: &BettingState,
+        actor: u8,
+        action: Action,
+        now: i64,
+    ) -> Result<BettingState, EngineError> {
+        if actor > 1 {
+            return Err(EngineError::InvalidPlayerIndex);
+        }
+        if !state.open || state.winner.is_some() {
+            return Err(EngineError::NotBettingRound);
+        }
+        if actor != state.to_act {
+            return Err(EngineError::NotYourTurn);
+        }
+        
+        let player = actor as usize;
+        let opponent = 1 - player;
+        if state.folded[player] {
+            return Err(EngineError::Folded);
+        }
+        if state.all_in[player] {
+            return Err(EngineError::AllIn);
+        }
+        if state.is_round_complete() {
+            return Err(EngineError::RoundComplete);
+        }
+        
+        let mut next = *state;
+        let current_bet = state.bets[player];
+        let opponent_bet = state.bets[opponent];
+        let player_stack = state.stacks[player];
+        let call_amount = opponent_bet.saturating_sub(current_bet);
+        
+        match action {
+            Action::Fold => {
+                next.folded[player] = true;
+                next.stacks[opponent] += next.pot;
+                next.pot = 0;
+                next.winner = Some(opponent as u8);
+                next.open = false;
+            }
+            
+            Action::Check => {
+                if current_bet != opponent_bet {
+                    return Err(EngineError::InvalidAction);
+                }
+            }
+            
+            Action::Call => {
+                // Without enough to call, the player goes all-in for what they have
+                // and the rest of the bet is returned when the street ends
+                next.put_in(player, call_amount.min(player_stack));
+            }
+            
+            Action::Raise(raise_amount) => {
+                if raise_amount == 0 {
+                    return Err(EngineError::InvalidBetAmount);
+                }
+                if player_stack < call_amount.saturating_add(raise_amount) {
+                    return Err(EngineError::InsufficientFunds);
+                }
+                self.raise(&mut next, player, raise_amount)?;
+            }
+            
+            Action::AllIn => {
+                if player_stack == 0 {
+                    return Err(EngineError::InsufficientFunds);
+                }
+                // Against an all-in opponent, going all-in only calls what they covered
+                if player_stack > call_amount && !state.all_in[opponent] {
+                    self.raise(&mut next, player, player_stack - call_amount)?;
+                } else {
+                    next.put_in(player, call_amount.min(player_stack));
+                }
+            }
+        }
+        
+        next.has_acted[player] = true;
+        if next.winner.is_none() && next.is_round_complete() {
+            next.return_uncalled_bet();
+        }
+        next.to_act = opponent as u8;
+        next.last_action_at = now;
+        next.action_deadline = now + self.action_timeout;
+        Ok(next)
+    }
+    
+    /// Call and raise by `raise_amount`, which the player's stack covers
+    fn raise(&self, state: &mut BettingState, player: usize, raise_amount: u64) -> Result<(), EngineError> {
+        let opponent = 1 - player;
+        
+        // The last raiser can only raise again after a full raise. In heads-up that
+        // means a short all-in never reopens the betting, and nobody raises an all-in
+        // player anyway.
+        if state.all_in[opponent] || state.last_aggressor == Some(player as u8) {
+            return Err(EngineError::BettingNotReopened);
+        }
+        
+        let total = state.bets[opponent].saturating_sub(state.bets[player]) + raise_amount;
+        let all_in = total == state.stacks[player];
+        
+        // A raise must be at least the size of the last full bet or raise, and at
+        // least the big blind, unless it puts the player all-in
+        let min_raise = state.last_raise_size.max(self.big_blind);
+        if raise_amount < min_raise && !all_in {
+            return Err(EngineError::MinimumRaiseNotMet);
+        }
+        if raise_amount >= min_raise {
+            state.last_raise_size = raise_amount;
+            state.last_aggressor = Some(player as u8);
+        }
+        
+        // Whatever the opponent can't call is returned when the street ends
+        state.put_in(player, total);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const ENGINE: HandEngine = HandEngine { small_blind: 1, big_blind: 2, action_timeout: 30 };
+    
+    /// Seat 0 deals and, posting the small blind, acts first pre-flop
+    fn seated(stacks: [u64; 2]) -> BettingState {
+        BettingState {
+            street: Street::PreFlop,
+            open: true,
+            dealer: 0,
+            to_act: 0,
+            stacks,
+            bets: [0, 0],
+            pot: 0,
+            last_raise_size: 0,
+            last_aggressor: None,
+            folded: [false; 2],
+            all_in: [false; 2],
+            has_acted: [false; 2],
+            winner: None,
+            last_action_at: 0,
+            action_deadline: 0,
+        }
+    }
+    
+    fn preflop(stacks: [u64; 2]) -> BettingState {
+        ENGINE.post_blinds(&seated(stacks)).unwrap()
+    }
+    
+    #[test]
+    fn test_blinds() {
+        let state = preflop([100, 100]);
+        assert_eq!((state.bets, state.stacks, state.pot), ([1, 2], [99, 98], 3));
+        assert_eq!((state.last_raise_size, state.last_aggressor), (2, None));
+        
+        assert_eq!(ENGINE.post_blinds(&seated([0, 100])), Err(EngineError::InsufficientStack));
+        assert_eq!(ENGINE.post_blinds(&seated([100, 1])), Err(EngineError::InsufficientStack));
+    }
+    
+    #[test]
+    fn test_turn_order() {
+        let state = preflop([100, 100]);
+        assert_eq!(ENGINE.apply(&state, 1, Action::Call, 10), Err(EngineError::NotYourTurn));
+        assert_eq!(ENGINE.apply(&state, 2, Action::Call, 10), Err(EngineError::InvalidPlayerIndex));
+        assert_eq!(ENGINE.apply(&state, 0, Action::Check, 10), Err(EngineError::InvalidAction));
+        
+        let closed = BettingState { open: false, ..state };
+        assert_eq!(ENGINE.apply(&closed, 0, Action::Call, 10), Err(EngineError::NotBettingRound));
+    }
+    
+    #[test]
+    fn test_fold_awards_pot() {
+        let state = ENGINE.apply(&preflop([100, 100]), 0, Action::Raise(2), 10).unwrap();
+        assert_eq!((state.bets, state.stacks, state.pot), ([4, 2], [96, 98], 6));
+        assert_eq!((state.to_act, state.last_action_at, state.action_deadline), (1, 10, 40));
+        
+        let state = ENGINE.apply(&state, 1, Action::Fold, 20).unwrap();
+        assert_eq!((state.winner, state.stacks, state.pot), (Some(0), [102, 98], 0));
+        assert_eq!(ENGINE.apply(&state, 0, Action::Check, 30), Err(EngineError::NotBettingRound));
+    }
+    
+    #[test]
+    fn test_call_and_next_street() {
+        let state = ENGINE.apply(&preflop([100, 100]), 0, Action::Call, 10).unwrap();
+        assert_eq!((state.bets, state.stacks, state.pot), ([2, 2], [98, 98], 4));
+        let state = ENGINE.apply(&state, 1, Action::Check, 20).unwrap();
+        assert!(state.is_round_complete());
+        
+        // The big blind acts first after the flop
+        let flop = state.next_street();
+        assert_eq!((flop.street, flop.bets, flop.to_act, flop.pot), (Street::Flop, [0, 0], 1, 4));
+        assert_eq!((flop.last_raise_size, flop.last_aggressor, flop.has_acted), (0, None, [false; 2]));
+        let river = flop.next_street().next_street().next_street();
+        assert_eq!(river.street, Street::River);
+    }
+    
+    #[test]
+    fn test_all_in() {
+        let state = ENGINE.apply(&preflop([10, 100]), 0, Action::AllIn, 10).unwrap();
+        assert_eq!((state.bets, state.stacks, state.all_in), ([10, 2], [0, 98], [true, false]));
+        assert!(!state.is_round_complete());
+        let flop = BettingState { to_act: 0, ..state.next_street() };
+        assert_eq!(ENGINE.apply(&flop, 0, Action::Check, 20), Err(EngineError::AllIn));
+        
+        // Calling for less than the bet goes all-in
+        let facing = BettingState { bets: [40, 2], stacks: [0, 5], pot: 42, all_in: [true, false], to_act: 1, ..state };
+        let state = ENGINE.apply(&facing, 1, Action::Call, 30).unwrap();
+        assert_eq!((state.stacks[1], state.all_in[1]), (0, true));
+        assert!(state.is_round_complete());
+    }
+    
+    #[test]
+    fn test_uncalled_bet_is_returned() {
+        // The short stack calls all-in and the rest of the all-in goes back
+        let state = ENGINE.apply(&preflop([100, 10]), 0, Action::AllIn, 10).unwrap();
+        assert_eq!((state.bets, state.stacks, state.pot), ([100, 2], [0, 8], 102));
+        let state = ENGINE.apply(&state, 1, Action::Call, 20).unwrap();
+        assert_eq!((state.bets, state.stacks, state.pot), ([10, 10], [90, 0], 20));
+        assert_eq!(state.all_in, [false, true]);
+        
+        // Nothing is left to bet against an all-in player
+        assert!(state.is_all_in_runout());
+        let flop = state.next_street();
+        assert!(flop.is_round_complete() && flop.is_all_in_runout());
+        
+        // The same with a raise, and with a short call that doesn't cover the blind
+        let state = play(preflop([100, 20]), &[Action::Raise(50), Action::Call]);
+        assert_eq!((state.bets, state.stacks, state.pot), ([20, 20], [80, 0], 40));
+        let facing = BettingState { bets: [40, 2], stacks: [0, 5], pot: 42, all_in: [true, false], to_act: 1, ..state };
+        let state = ENGINE.apply(&facing, 1, Action::Call, 30).unwrap();
+        assert_eq!((state.bets, state.stacks, state.pot), ([7, 7], [33, 0], 14));
+        
+        // Going all-in against an all-in player only calls what they covered
+        let state = play(preflop([100, 30]), &[Action::Raise(20), Action::AllIn, Action::AllIn]);
+        assert_eq!((state.bets, state.stacks, state.pot), ([30, 30], [70, 0], 60));
+        assert_eq!(state.all_in, [false, true]);
+    }
+    
+    /// Play `actions` in turn from `state`
+    fn play(mut state: BettingState, actions: &[Action]) -> BettingState {
+        for (i, action) in actions.iter().enumerate() {
+            assert!(!state.is_round_complete());
+            state = ENGINE.apply(&state, state.to_act, *action, i as i64).unwrap();
+        }
+        state
+    }
+    
+    #[test]
+    fn test_check_check() {
+        let flop = play(preflop([100, 100]), &[Action::Call, Action::Check]).next_street();
+        assert!(!flop.is_round_complete());
+        let state = play(flop, &[Action::Check]);
+        assert!(!state.is_round_complete() && state.needs_to_act(0));
+        let state = play(state, &[Action::Check]);
+        assert!(state.is_round_complete());
+        assert_eq!(ENGINE.apply(&state, 1, Action::Check, 10), Err(EngineError::RoundComplete));
+    }
+    
+    #[test]
+    fn test_bet_call() {
+        let flop = play(preflop([100, 100]), &[Action::Call, Action::Check]).next_street();
+        let state = play(flop, &[Action::Raise(10)]);
+        assert!(!state.is_round_complete() && state.needs_to_act(0));
+        let state = play(state, &[Action::Call]);
+        assert!(state.is_round_complete() && !state.is_all_in_runout());
+        assert_eq!((state.bets, state.pot), ([10, 10], 24));
+        
+        // Check, bet, raise, call
+        let state = play(flop, &[Action::Check, Action::Raise(4), Action::Raise(8), Action::Call]);
+        assert!(state.is_round_complete());
+        assert_eq!(state.bets, [12, 12]);
+    }
+    
+    #[test]
+    fn test_limp_check() {
+        // The small blind completes, and the big blind still has the option
+        let state = play(preflop([100, 100]), &[Action::Call]);
+        assert!(!state.is_round_complete() && state.needs_to_act(1));
+        let state = play(state, &[Action::Check]);
+        assert!(state.is_round_complete());
+        assert_eq!((state.bets, state.pot), ([2, 2], 4));
+    }
+    
+    #[test]
+    fn test_limp_raise() {
+        let state = play(preflop([100, 100]), &[Action::Call, Action::Raise(6)]);
+        assert!(!state.is_round_complete() && state.needs_to_act(0));
+        assert_eq!((state.bets, state.last_aggressor), ([2, 8], Some(1)));
+        let state = play(state, &[Action::Call]);
+        assert!(state.is_round_complete());
+        assert_eq!((state.bets, state.pot), ([8, 8], 16));
+    }
+    
+    #[test]
+    fn test_all_in_blinds_end_the_round() {
+        // The big blind is all-in, so the small blind only decides whether to call
+        let state = preflop([100, 2]);
+        assert_eq!(state.all_in, [false, true]);
+        assert!(state.needs_to_act(0));
+        assert!(play(state, &[Action::Call]).is_round_complete());
+        
+        // A small blind that is all-in leaves nothing to decide, and only calls
+        // half of the big blind
+        let state = preflop([1, 100]);
+        assert_eq!(state.all_in, [true, false]);
+        assert!(state.is_round_complete());
+        assert_eq!((state.bets, state.stacks, state.pot), ([1, 1], [0, 99], 2));
+    }
+    
+    #[test]
+    fn test_raise_rules() {
+        let state = preflop([100, 100]);
+        assert_eq!(ENGINE.apply(&state, 0, Action::Raise(99), 10), Err(EngineError::InsufficientFunds));
+        
+        let state = ENGINE.apply(&state, 0, Action::Raise(10), 10).unwrap();
+        assert_eq!(state.bets, [12, 2]);
+        assert_eq!(ENGINE.apply(&state, 1, Action::Raise(1), 20), Err(EngineError::MinimumRaiseNotMet));
+        
+        // Raising all-in is allowed below the minimum
+        let short = BettingState { stacks: [88, 15], ..state };
+        let raised = ENGINE.apply(&short, 1, Action::Raise(5), 20).unwrap();
+        assert_eq!((raised.bets, raised.stacks[1]), ([12, 17], 0));
+        
+        // A bet can be larger than the opponent's stack
+        let state = ENGINE.apply(&preflop([100, 20]), 0, Action::Raise(50), 10).unwrap();
+        assert_eq!(state.bets[0], 52);
+    }
+    
+    #[test]
+    fn test_min_raise_tracking() {
+        // The big blind is the initial minimum
+        let state = preflop([100, 100]);
+        assert_eq!(ENGINE.apply(&state, 0, Action::Raise(1), 10), Err(EngineError::MinimumRaiseNotMet));
+        assert_eq!(ENGINE.apply(&state, 0, Action::Raise(0), 10), Err(EngineError::InvalidBetAmount));
+        
+        // Raising a call by one chip isn't a raise
+        let called = ENGINE.apply(&state, 0, Action::Call, 10).unwrap();
+        assert_eq!(ENGINE.apply(&called, 1, Action::Raise(1), 20), Err(EngineError::MinimumRaiseNotMet));
+        assert!(ENGINE.apply(&called, 1, Action::Raise(2), 20).is_ok());
+        
+        // Each raise must be at least the last one
+        let state = ENGINE.apply(&state, 0, Action::Raise(4), 10).unwrap();
+        assert_eq!((state.bets, state.last_raise_size, state.last_aggressor), ([6, 2], 4, Some(0)));
+        assert_eq!(ENGINE.apply(&state, 1, Action::Raise(3), 20), Err(EngineError::MinimumRaiseNotMet));
+        let state = ENGINE.apply(&state, 1, Action::Raise(10), 20).unwrap();
+        assert_eq!((state.bets, state.last_raise_size, state.last_aggressor), ([6, 16], 10, Some(1)));
+        assert_eq!(ENGINE.apply(&state, 0, Action::Raise(9), 30), Err(EngineError::MinimumRaiseNotMet));
+        
+        // Post-flop, the minimum bet is the big blind
+        let flop = ENGINE.apply(&called, 1, Action::Check, 20).unwrap().next_street();
+        assert_eq!(ENGINE.apply(&flop, 1, Action::Raise(1), 30), Err(EngineError::MinimumRaiseNotMet));
+        let state = ENGINE.apply(&flop, 1, Action::Raise(2), 30).unwrap();
+        assert_eq!((state.bets, state.last_raise_size, state.last_aggressor), ([0, 2], 2, Some(1)));
+    }
+    
+    #[test]
+    fn test_short_all_in_does_not_reopen_betting() {
+        let state = ENGINE.apply(&preflop([100, 30]), 0, Action::Raise(20), 10).unwrap();
+        assert_eq!((state.bets, state.last_aggressor), ([22, 2], Some(0)));
+        
+        // The all-in raises by 8, less than the last raise of 20
+        let state = ENGINE.apply(&state, 1, Action::AllIn, 20).unwrap();
+        assert_eq!((state.bets, state.stacks[1], state.all_in[1]), ([22, 30], 0, true));
+        assert_eq!((state.last_raise_size, state.last_aggressor), (20, Some(0)));
+        
+        assert_eq!(ENGINE.apply(&state, 0, Action::Raise(20), 30), Err(EngineError::BettingNotReopened));
+        assert_eq!(ENGINE.apply(&state, 0, Action::Call, 30).unwrap().bets, [30, 30]);
+    }
+    
+    /// Every action, with every raise size up to the larger stack
+    fn actions(state: &BettingState) -> impl Iterator<Item = Action> {
+        let max_raise = state.stacks[0].max(state.stacks[1]) + 1;
+        [Action::Fold, Action::Check, Action::Call, Action::AllIn]
+            .into_iter()
+            .chain((0..=max_raise).map(Action::Raise))
+    }
+    
+    /// Depth-first search over every sequence of actions and street changes up to
+    /// `depth`, checking the invariants of each state reached
+    fn explore(state: &BettingState, depth: i64, total: u64, visited: &mut usize) {
+        *visited += 1;
+        assert_eq!(state.total_chips(), total);
+        if state.winner.is_some() {
+            assert!(!state.open);
+            assert_eq!(state.pot, 0);
+        } else if state.is_round_complete() {
+            // Every chip in the pot has been matched
+            assert_eq!(state.bets[0], state.bets[1]);
+            assert!((0..2).all(|i| !state.all_in[i] || state.stacks[i] == 0));
+        } else {
+            assert!((0..2).all(|i| !state.all_in[i] || state.stacks[i] == 0));
+        }
+        if depth == 0 {
+            return;
+        }
+        
+        // The seat to act can always fold until the round is complete, and nobody can
+        // act after
+        let fold = ENGINE.apply(state, state.to_act, Action::Fold, 0);
+        if state.open && state.winner.is_none() && !state.is_round_complete() {
+            assert!(fold.is_ok());
+        } else {
+            assert!(fold.is_err());
+        }
+        
+        for actor in 0..3u8 {
+            for action in actions(state) {
+                match ENGINE.apply(state, actor, action, depth) {
+                    Ok(next) => {
+                        assert_eq!(actor, state.to_act);
+                        assert_eq!(next.to_act, 1 - actor);
+                        assert_eq!(next.action_deadline, depth + ENGINE.action_timeout);
+                        assert!(next.pot >= state.pot || next.winner.is_some() || next.is_round_complete());
+                        explore(&next, depth - 1, total, visited);
+                    }
+                    Err(EngineError::InvalidPlayerIndex) => assert_eq!(actor, 2),
+                    Err(EngineError::NotBettingRound) => assert!(state.winner.is_some()),
+                    Err(EngineError::NotYourTurn) => assert_ne!(actor, state.to_act),
+                    Err(EngineError::AllIn) => assert!(state.all_in[actor as usize]),
+                    Err(EngineError::BettingNotReopened) => assert!(
+                        state.all_in[1 - actor as usize] || state.last_aggressor == Some(actor)
+                    ),
+                    Err(EngineError::InsufficientStack) => panic!("blinds are only posted once"),
+                    Err(_) => {}
+                }
+            }
+        }
+        
+        if state.winner.is_none() && state.is_round_complete() && state.street != Street::River {
+            explore(&state.next_street(), depth - 1, total, visited);
+        }
+    }
+    
+    #[test]
+    fn test_exhaustive_small_stacks() {
+        let mut visited = 0;
+        for stacks in [[8, 8], [5, 12], [12, 3], [2, 9]] {
+            explore(&preflop(stacks), 12, stacks[0] + stacks[1], &mut visited);
+        }
+        assert!(visited > 10_000);
+    }
+}
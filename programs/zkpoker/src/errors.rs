@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use crate::engine::EngineError;
+use crate::state::GameError;
 
 #[error_code]
 pub enum PokerError {
@@ -106,5 +108,155 @@ pub enum PokerError {
     
     #[msg("Invalid ephemeral public key")]
     InvalidEphemeralKey,
+    
+    #[msg("Invalid Paillier public key")]
+    InvalidPaillierKey,
+    
+    #[msg("This game is invite-only")]
+    NotInvited,
+    
+    #[msg("Invalid pocket cards")]
+    InvalidPocketCards,
+    
+    #[msg("Cannot claim a timeout on your own pending action")]
+    CannotClaimOwnTimeout,
+
+    #[msg("The current hand is still in progress")]
+    HandInProgress,
+
+    #[msg("Game has already concluded")]
+    GameConcluded,
+
+    #[msg("Game vault balance does not match the chips in play")]
+    VaultBalanceMismatch,
+
+    #[msg("Player balance account does not belong to this game")]
+    InvalidPlayerBalance,
+
+    #[msg("Malformed zero-knowledge proof data")]
+    InvalidProofData,
+
+    #[msg("Invalid public inputs for proof verification")]
+    InvalidPublicInputs,
+
+    #[msg("Zero-knowledge proof verification failed")]
+    ProofVerificationFailed,
+
+    #[msg("No stored proof matches the disputed action")]
+    NoProofToDispute,
+
+    #[msg("Cannot dispute your own proof")]
+    CannotDisputeOwnProof,
+
+    #[msg("A dispute is already in progress")]
+    DisputeInProgress,
+
+    #[msg("Challenger has no bond left to back a dispute")]
+    InsufficientBond,
+
+    #[msg("Deck buffer does not belong to the current hand")]
+    InvalidDeckBuffer,
+
+    #[msg("Deck has not been committed yet")]
+    DeckNotCommitted,
+
+    #[msg("Merkle proof does not match the deck commitment")]
+    InvalidMerkleProof,
+
+    #[msg("Deck has been fully uploaded")]
+    DeckAlreadyAvailable,
+
+    #[msg("Invalid verification job inputs")]
+    InvalidVerificationJob,
+
+    #[msg("Montgomery R^2 hint does not match the modulus")]
+    InvalidMontgomeryHint,
+
+    #[msg("Verification job has already completed")]
+    VerificationComplete,
+
+    #[msg("Bisection move is not valid in the current round")]
+    InvalidBisectionMove,
+
+    #[msg("Ciphertext is not a valid element of Z_n^2")]
+    InvalidCiphertext,
+
+    #[msg("Plaintext is out of range for the Paillier key")]
+    InvalidPlaintext,
+
+    #[msg("Encryption randomness is out of range for the Paillier key")]
+    InvalidEncryptionRandomness,
+
+    #[msg("Paillier modulus must be exactly 2048 bits")]
+    PaillierModulusWrongSize,
+
+    #[msg("Paillier modulus must be odd")]
+    PaillierModulusEven,
+
+    #[msg("Paillier generator must be n + 1")]
+    PaillierGeneratorMismatch,
+
+    #[msg("Ciphertext length does not match the Paillier key size")]
+    CiphertextWrongLength,
+
+    #[msg("Ciphertext must be a non-zero value below n^2")]
+    CiphertextOutOfRange,
+
+    #[msg("Invalid Ristretto255 point encoding")]
+    InvalidCurvePoint,
+
+    #[msg("Scalar must be a canonical Ristretto255 scalar")]
+    InvalidCurveScalar,
+
+    #[msg("Both players must use the same card encryption backend")]
+    CardBackendMismatch,
+
+    #[msg("Operation is not supported by this match's card backend")]
+    UnsupportedCardBackend,
+
+    #[msg("Decryption share proof is invalid")]
+    InvalidDecryptionProof,
+
+    #[msg("Verifying key account does not match the version pinned by this game")]
+    VerifyingKeyMismatch,
+
+    #[msg("Verifying key has the wrong number of public inputs for its circuit")]
+    InvalidVerifyingKey,
+
+    #[msg("Only the registry admin can do this")]
+    NotRegistryAdmin,
+
+    #[msg("Cannot raise - a short all-in did not reopen the betting")]
+    BettingNotReopened,
+
+    #[msg("Betting round is complete and must be advanced")]
+    BettingRoundComplete,
+
+    #[msg("No verifying key has been published for this circuit")]
+    VerifyingKeyNotPublished,
+
+    #[msg("Revealed Paillier secret is not invertible modulo n")]
+    InvalidPaillierSecret,
+
+    #[msg("A zero-knowledge proof is required for every revealed card")]
+    MissingProof,
 }
 
+impl From<EngineError> for anchor_lang::error::Error {
+    fn from(error: EngineError) -> Self {
+        match error {
+            EngineError::NotBettingRound => PokerError::InvalidGameStage.into(),
+            EngineError::NotYourTurn => PokerError::NotYourTurn.into(),
+            EngineError::Folded => PokerError::CannotActAfterFold.into(),
+            EngineError::AllIn => PokerError::CannotRaiseAllIn.into(),
+            EngineError::InvalidAction => PokerError::InvalidAction.into(),
+            EngineError::InvalidBetAmount => PokerError::InvalidBetAmount.into(),
+            EngineError::InsufficientFunds => PokerError::InsufficientFunds.into(),
+            EngineError::MinimumRaiseNotMet => PokerError::MinimumRaiseNotMet.into(),
+            EngineError::BettingNotReopened => PokerError::BettingNotReopened.into(),
+            EngineError::RoundComplete => PokerError::BettingRoundComplete.into(),
+            EngineError::InvalidPlayerIndex => GameError::InvalidPlayerIndex.into(),
+            EngineError::InsufficientStack => GameError::InsufficientStack.into(),
+        }
+    }
+}
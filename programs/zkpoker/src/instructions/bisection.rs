@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use super::verification::{disputed_card_index, disputed_decryption, settle_decryption_dispute};
+
+/// Open a bisection game over a disputed card decryption (accused player)
+/// The accused reveals their Paillier secret λ and μ = λ^-1 mod n together with an
+/// R^2 mod n^2 hint, then posts the first midpoint. Every round must be played within
+/// the action timeout.
+pub fn open_bisection(
+    ctx: Context<OpenBisection>,
+    lambda: Vec<u8>,
+    lambda_inverse: Vec<u8>,
+    montgomery_r2: Vec<u8>,
+) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player = ctx.accounts.player.key();
+    let clock = Clock::get()?;
+    
+    let card_index = match game.hand.disputed_action {
+        DisputedAction::CardDecryptionBisection { card_index } => card_index,
+        _ => return err!(PokerError::InvalidGameStage),
+    };
+    require!(
+        game.hand.stage == HandStage::Dispute && game.hand.dispute_active,
+        PokerError::InvalidGameStage
+    );
+    
+    let challenger_index = game.hand.challenger_index;
+    let accused_index = 1 - challenger_index;
+    require!(
+        game.get_player_index(&player)? == accused_index,
+        PokerError::NotYourTurn
+    );
+    
+    let (ciphertext, claim) = disputed_decryption(
+        game,
+        ctx.accounts.deck_buffer.as_deref(),
+        accused_index,
+        card_index,
+    )?;
+    
+    let bisection = &mut ctx.accounts.bisection;
+    bisection.game = game.key();
+    bisection.hand_id = game.current_hand_id;
+    bisection.card_index = card_index;
+    bisection.accused_index = accused_index;
+    bisection.challenger_index = challenger_index;
+    bisection.payer = player;
+    bisection.bump = *ctx.bumps.get("bisection").unwrap();
+    bisection.start(
+        &game.paillier_pks[accused_index as usize].n,
+        lambda,
+        &lambda_inverse,
+        &ciphertext,
+        &claim,
+        montgomery_r2,
+    )?;
+    
+    if bisection.status == VerificationStatus::InProgress {
+        // The accused posts the first midpoint
+        game.mark_action(&clock);
+    } else {
+        settle_decryption_dispute(game, card_index, challenger_index, bisection.status, &clock)?;
+    }
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OpenBisection<'info> {
+    #[account(mut)]
+    pub game: Box<Account<'info, Game>>,
+    
+    #[account(
+        init,
+        payer = player,
+        space = BisectionGame::LEN,
+        seeds = [
+            b"bisection",
+            game.key().as_ref(),
+            &game.current_hand_id.to_le_bytes(),
+            &[disputed_card_index(&game.hand.disputed_action)]
+        ],
+        bump
+    )]
+    pub bisection: Box<Account<'info, BisectionGame>>,
+    
+    /// Only needed when the dealer is accused
+    #[account(
+        seeds = [
+            b"deck_buffer",
+            game.key().as_ref(),
+            &game.current_hand_id.to_le_bytes()
+        ],
+        bump = deck_buffer.bump
+    )]
+    pub deck_buffer: Option<Account<'info, DeckBuffer>>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Post the accumulator value at the midpoint of the disputed segment (accused player)
+pub fn bisection_post_midpoint(ctx: Context<BisectionMove>, value: Vec<u8>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let bisection = &mut ctx.accounts.bisection;
+    let player = ctx.accounts.player.key();
+    let clock = Clock::get()?;
+    
+    require_active_bisection(game, bisection)?;
+    require!(
+        game.get_player_index(&player)? == bisection.accused_index,
+        PokerError::NotYourTurn
+    );
+    
+    bisection.post_midpoint(&value)?;
+    
+    // The challenger now has to agree or disagree with the midpoint
+    game.hand.current_turn_index = bisection.challenger_index;
+    game.mark_action(&clock);
+    
+    Ok(())
+}
+
+/// Accept or reject the posted midpoint (challenger)
+/// Once the segment is a single exponentiation step it is checked on-chain and the
+/// dispute is settled.
+pub fn bisection_choose(ctx: Context<BisectionMove>, agree_with_midpoint: bool) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let bisection = &mut ctx.accounts.bisection;
+    let player = ctx.accounts.player.key();
+    let clock = Clock::get()?;
+    
+    require_active_bisection(game, bisection)?;
+    require!(
+        game.get_player_index(&player)? == bisection.challenger_index,
+        PokerError::NotYourTurn
+    );
+    
+    bisection.choose(agree_with_midpoint)?;
+    
+    if bisection.status == VerificationStatus::InProgress {
+        game.hand.current_turn_index = bisection.accused_index;
+        game.mark_action(&clock);
+    } else {
+        settle_decryption_dispute(
+            game,
+            bisection.card_index,
+            bisection.challenger_index,
+            bisection.status,
+            &clock,
+        )?;
+    }
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct BisectionMove<'info> {
+    #[account(mut)]
+    pub game: Box<Account<'info, Game>>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"bisection",
+            game.key().as_ref(),
+            &bisection.hand_id.to_le_bytes(),
+            &[bisection.card_index]
+        ],
+        bump = bisection.bump
+    )]
+    pub bisection: Box<Account<'info, BisectionGame>>,
+    
+    pub player: Signer<'info>,
+}
+
+fn require_active_bisection(game: &Game, bisection: &BisectionGame) -> Result<()> {
+    require!(
+        bisection.hand_id == game.current_hand_id
+            && game.hand.dispute_active
+            && game.hand.disputed_action
+                == (DisputedAction::CardDecryptionBisection { card_index: bisection.card_index }),
+        PokerError::InvalidBisectionMove
+    );
+    Ok(())
+}
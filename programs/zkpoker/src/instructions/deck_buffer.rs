@@ -0,0 +1,213 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+/// Create the deck buffer for the current hand
+/// Either player can create it; the decks are then uploaded with append_deck_chunk
+pub fn init_deck_buffer(ctx: Context<InitDeckBuffer>) -> Result<()> {
+    let game = &ctx.accounts.game;
+    let player = ctx.accounts.player.key();
+    
+    game.get_player_index(&player)?;
+    require!(
+        game.current_hand_id > 0 && game.hand.stage != HandStage::Complete,
+        PokerError::InvalidGameStage
+    );
+    
+    let deck_buffer = &mut ctx.accounts.deck_buffer;
+    deck_buffer.game = game.key();
+    deck_buffer.hand_id = game.current_hand_id;
+    deck_buffer.payer = player;
+    deck_buffer.received = [0, 0];
+    deck_buffer.cards = Default::default();
+    deck_buffer.bump = *ctx.bumps.get("deck_buffer").unwrap();
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitDeckBuffer<'info> {
+    pub game: Box<Account<'info, Game>>,
+    
+    #[account(
+        init,
+        payer = player,
+        space = DeckBuffer::BASE_LEN,
+        seeds = [
+            b"deck_buffer",
+            game.key().as_ref(),
+            &game.current_hand_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub deck_buffer: Account<'info, DeckBuffer>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Upload encrypted cards of a committed deck
+/// Only the player who committed to the deck can upload it, and every card must come
+/// with a Merkle proof against that commitment. The buffer grows to fit each chunk.
+pub fn append_deck_chunk(
+    ctx: Context<AppendDeckChunk>,
+    deck: DeckKind,
+    cards: Vec<CommittedCard>,
+) -> Result<()> {
+    let game = &ctx.accounts.game;
+    let deck_buffer = &mut ctx.accounts.deck_buffer;
+    let player = ctx.accounts.player.key();
+    
+    require!(!cards.is_empty(), PokerError::InvalidEncryptedCards);
+    require!(
+        deck_buffer.hand_id == game.current_hand_id,
+        PokerError::InvalidDeckBuffer
+    );
+    
+    let player_index = game.get_player_index(&player)?;
+    require!(
+        player_index == game.deck_uploader_index(deck),
+        PokerError::NotYourTurn
+    );
+    
+    for card in cards {
+        game.validate_deck_ciphertext(deck, &card.ciphertext)?;
+        game.verify_committed_card(deck, &card)?;
+        deck_buffer.append_card(deck, card.card_index, card.ciphertext)?;
+    }
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(deck: DeckKind, cards: Vec<CommittedCard>)]
+pub struct AppendDeckChunk<'info> {
+    pub game: Box<Account<'info, Game>>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"deck_buffer",
+            game.key().as_ref(),
+            &deck_buffer.hand_id.to_le_bytes()
+        ],
+        bump = deck_buffer.bump,
+        realloc = deck_buffer.to_account_info().data_len() + DeckBuffer::chunk_space(&cards),
+        realloc::payer = player,
+        realloc::zero = false
+    )]
+    pub deck_buffer: Account<'info, DeckBuffer>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Claim the hand when the opponent never made their committed deck available
+/// The uploader forfeits the pot and their bond if the deck is still incomplete
+/// once the upload window has passed.
+pub fn claim_data_availability_timeout(
+    ctx: Context<ClaimDataAvailabilityTimeout>,
+    deck: DeckKind,
+) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let deck_buffer = &ctx.accounts.deck_buffer;
+    let player = ctx.accounts.player.key();
+    let clock = Clock::get()?;
+    
+    require!(
+        game.game_status == GameStatus::Active,
+        PokerError::InvalidGameStage
+    );
+    require!(
+        game.current_hand_id > 0 && game.hand.stage != HandStage::Complete,
+        PokerError::InvalidGameStage
+    );
+    require!(!game.hand.dispute_active, PokerError::DisputeInProgress);
+    require!(
+        deck_buffer.hand_id == game.current_hand_id,
+        PokerError::InvalidDeckBuffer
+    );
+    
+    let claimer_index = game.get_player_index(&player)?;
+    let uploader_index = game.deck_uploader_index(deck);
+    require!(
+        claimer_index != uploader_index,
+        PokerError::CannotClaimOwnTimeout
+    );
+    
+    let deadline = game
+        .deck_upload_deadline(deck)
+        .ok_or(PokerError::DeckNotCommitted)?;
+    require!(clock.unix_timestamp > deadline, PokerError::TimeoutNotReached);
+    require!(
+        !deck_buffer.is_complete(deck),
+        PokerError::DeckAlreadyAvailable
+    );
+    
+    msg!("Deck was not made available - slashing player {}", uploader_index);
+    game.forfeit_hand(claimer_index, uploader_index)?;
+    game.mark_action(&clock);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimDataAvailabilityTimeout<'info> {
+    #[account(mut)]
+    pub game: Box<Account<'info, Game>>,
+    
+    #[account(
+        seeds = [
+            b"deck_buffer",
+            game.key().as_ref(),
+            &deck_buffer.hand_id.to_le_bytes()
+        ],
+        bump = deck_buffer.bump
+    )]
+    pub deck_buffer: Account<'info, DeckBuffer>,
+    
+    pub player: Signer<'info>,
+}
+
+/// Close a deck buffer once its hand is over and return the rent to its payer
+pub fn close_deck_buffer(ctx: Context<CloseDeckBuffer>) -> Result<()> {
+    let game = &ctx.accounts.game;
+    let deck_buffer = &ctx.accounts.deck_buffer;
+    
+    require!(
+        deck_buffer.hand_id < game.current_hand_id
+            || game.hand.stage == HandStage::Complete
+            || game.game_status == GameStatus::Concluded,
+        PokerError::HandInProgress
+    );
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseDeckBuffer<'info> {
+    pub game: Box<Account<'info, Game>>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"deck_buffer",
+            game.key().as_ref(),
+            &deck_buffer.hand_id.to_le_bytes()
+        ],
+        bump = deck_buffer.bump,
+        close = payer
+    )]
+    pub deck_buffer: Account<'info, DeckBuffer>,
+    
+    /// CHECK: Receives the buffer's rent, must be the account that paid for it
+    #[account(
+        mut,
+        address = deck_buffer.payer
+    )]
+    pub payer: AccountInfo<'info>,
+}
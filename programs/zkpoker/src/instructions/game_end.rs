@@ -1,307 +1,447 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
 use crate::state::*;
 use crate::errors::*;
 use crate::poker;
+use crate::zk;
 
 /// Resolve hand at showdown - Two step process
 /// Step 1: First player reveals their pocket cards
 /// Step 2: Second player reveals their pocket cards, then winner is determined
-pub fn resolve_hand(mut ctx: Context<ResolveGame>) -> Result<()> {
-    let game = &mut ctx.accounts.game_state;
+/// Each reveal includes the doubly-encrypted pocket cards with their Merkle proofs.
+/// The hand settles as soon as both players have revealed, leaving no time for a dispute,
+/// so the pocket cards are proven here: by a ProveCorrectShowdownReveal proof in Paillier
+/// matches, and in ElGamal matches by both players' Chaum–Pedersen-proven decryption shares,
+/// which are combined on-chain.
+pub fn resolve_hand(
+    ctx: Context<ResolveGame>,
+    pocket_cards: [u8; 2],
+    revealed_cards: CardMultiproof,
+    showdown_proof: Option<ZkProof>,
+    pocket_shares: Vec<PocketCardShares>,
+) -> Result<()> {
+    let game = &mut ctx.accounts.game;
     let player = ctx.accounts.player.key();
     let clock = Clock::get()?;
     
     require!(
-        game.stage == GameStage::Showdown || game.stage == GameStage::AwaitingPlayer2ShowdownReveal,
+        game.hand.stage == HandStage::Showdown,
         PokerError::InvalidGameStage
     );
     
-    let is_player1 = player == game.player1;
+    let player_index = game.get_player_index(&player)?;
+    let opponent_index = 1 - player_index;
     
-    if game.stage == GameStage::Showdown {
-        // First player revealing their hand
-        require!(
-            !game.player1_revealed_hand && !game.player2_revealed_hand,
-            PokerError::AlreadyRevealedHand
-        );
-        
-        // Mark player as having revealed
-        if is_player1 {
-            game.player1_revealed_hand = true;
-        } else {
-            game.player2_revealed_hand = true;
-        }
-        
-        // Set deadline for other player to reveal
-        game.reveal_deadline = clock.unix_timestamp + game.action_timeout;
-        game.stage = GameStage::AwaitingPlayer2ShowdownReveal;
-        game.last_action_at = clock.unix_timestamp;
-        
-        return Ok(());
-    }
+    require!(
+        !game.hand.player_revealed_showdown[player_index as usize],
+        PokerError::AlreadyRevealedHand
+    );
+    require!(
+        pocket_cards[0] < DECK_SIZE && pocket_cards[1] < DECK_SIZE && pocket_cards[0] != pocket_cards[1],
+        PokerError::InvalidPocketCards
+    );
     
-    if game.stage == GameStage::AwaitingPlayer2ShowdownReveal {
-        // Second player revealing their hand
-        require!(
-            (is_player1 && !game.player1_revealed_hand) || (!is_player1 && !game.player2_revealed_hand),
-            PokerError::AlreadyRevealedHand
-        );
-        
-        // Mark player as having revealed
-        if is_player1 {
-            game.player1_revealed_hand = true;
-        } else {
-            game.player2_revealed_hand = true;
+    let pocket_card_indices = game.pocket_card_indices(player_index);
+    game.verify_revealed_cards(&pocket_card_indices, &revealed_cards)?;
+    match game.card_backend {
+        CardBackend::Paillier => {
+            let proof = showdown_proof.ok_or(PokerError::MissingProof)?;
+            let vk = game.verifying_key(ProofCircuit::ShowdownReveal, ctx.accounts.verifying_key.as_deref())?;
+            let player_key = zk::card_keys_to_field_elements(game)[player_index as usize];
+            zk::verify_showdown_reveal_proof(
+                &vk,
+                &proof,
+                &game.hand.doubly_encrypted_deck_merkle_root,
+                &pocket_cards,
+                &player_key,
+            )?;
         }
-        
-        // Verify both players' pocket cards against encrypted versions
-        let p1_encrypted = game.get_player1_encrypted_cards();
-        let p2_encrypted = game.get_player2_encrypted_cards();
-        
-        // TODO: reinstate verification
-        // // Verify player 1's cards
-        // require!(
-        //     game.verify_card(game.player1_hand[0], &p1_encrypted[0]),
-        //     PokerError::CardVerificationFailed
-        // );
-        // require!(
-        //     game.verify_card(game.player1_hand[1], &p1_encrypted[1]),
-        //     PokerError::CardVerificationFailed
-        // );
-        
-        // // Verify player 2's cards
-        // require!(
-        //     game.verify_card(game.player2_hand[0], &p2_encrypted[0]),
-        //     PokerError::CardVerificationFailed
-        // );
-        // require!(
-        //     game.verify_card(game.player2_hand[1], &p2_encrypted[1]),
-        //     PokerError::CardVerificationFailed
-        // );
-        
-        // Evaluate both hands
-        let player1_score = poker::find_best_hand(&game.player1_hand, &game.community_cards).1;
-        let player2_score = poker::find_best_hand(&game.player2_hand, &game.community_cards).1;
-        
-        // Store pot value before modification
-        let pot_amount = game.pot;
-        let stake = game.stake_amount as i64;
-        
-        // Return bonds to both players
-        let total_amount = pot_amount + game.player1_bond + game.player2_bond;
-        
-        // Determine winner and winnings (including bond returns)
-        let (p1_win, p2_win) = if player1_score > player2_score {
-            game.winner = Some(game.player1);
-            game.winning_hand_rank = Some((player1_score >> 20) as u16);
-            (pot_amount + game.player1_bond, game.player2_bond)
-        } else if player2_score > player1_score {
-            game.winner = Some(game.player2);
-            game.winning_hand_rank = Some((player2_score >> 20) as u16);
-            (game.player1_bond, pot_amount + game.player2_bond)
-        } else {
-            game.winner = None;
-            let pot_split = pot_amount / 2;
-            (pot_split + game.player1_bond, pot_split + game.player2_bond)
-        };
-        
-        game.stage = GameStage::Finished;
-        
-        // Transfer total amount from game vault to program vault
-        if total_amount > 0 {
-            transfer_pot_to_vault(&ctx, total_amount)?;
+        CardBackend::ElGamalRistretto => {
+            let cards = game.decrypt_pocket_cards(player_index, &revealed_cards, &pocket_shares)?;
+            require!(cards == pocket_cards, PokerError::InvalidPocketCards);
         }
-        
-        // Update stats and balances
-        update_player_stats(&mut ctx.accounts, p1_win, p2_win, stake)?;
     }
+    game.reveal_pocket_cards(player_index, pocket_cards)?;
     
-    Ok(())
-}
-
-/// Compatibility alias for resolve_hand
-pub fn resolve_game(ctx: Context<ResolveGame>) -> Result<()> {
-    resolve_hand(ctx)
-}
-
-fn transfer_pot_to_vault(ctx: &Context<ResolveGame>, pot_amount: u64) -> Result<()> {
-    let game = &ctx.accounts.game_state;
-    let game_id = game.game_id;
-    let player1_key = game.player1;
-    let vault_bump = game.vault_bump;
-    let seeds = &[
-        b"game_vault".as_ref(),
-        player1_key.as_ref(),
-        &game_id.to_le_bytes(),
-        &[vault_bump],
+    if !game.hand.player_revealed_showdown[opponent_index as usize] {
+        // Other player now has to reveal before the action deadline
+        game.hand.current_turn_index = opponent_index;
+        game.mark_action(&clock);
+        return Ok(());
+    }
+    
+    // Both hands are revealed - evaluate them against the board
+    let mut board = [0u8; 5];
+    for (slot, card) in board.iter_mut().zip(game.hand.community_cards.iter()) {
+        *slot = card.ok_or(PokerError::InvalidCommunityCards)?;
+    }
+    let hands = [
+        game.hand.pocket_cards[0].ok_or(PokerError::OpponentNotRevealed)?,
+        game.hand.pocket_cards[1].ok_or(PokerError::OpponentNotRevealed)?,
     ];
-    let signer = &[&seeds[..]];
     
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.game_vault.to_account_info(),
-            to: ctx.accounts.program_vault.to_account_info(),
-            authority: ctx.accounts.game_vault.to_account_info(),
-        },
-        signer
-    );
-    token::transfer(cpi_ctx, pot_amount)
-}
-
-fn update_player_stats(
-    accounts: &mut ResolveGame,
-    p1_win: u64,
-    p2_win: u64,
-    stake: i64,
-) -> Result<()> {
-    accounts.player1_account.total_hands_played += 1;
-    accounts.player2_account.total_hands_played += 1;
-    
-    if p1_win > 0 {
-        accounts.player1_account.total_hands_won += 1;
-        accounts.player1_account.total_winnings = accounts.player1_account
-            .total_winnings.saturating_add(p1_win as i64).saturating_sub(stake);
-        accounts.player2_account.total_winnings = accounts.player2_account
-            .total_winnings.saturating_sub(stake);
-        accounts.player1_balance.balance = accounts.player1_balance.balance.saturating_add(p1_win);
-    } else if p2_win > 0 {
-        accounts.player2_account.total_hands_won += 1;
-        accounts.player2_account.total_winnings = accounts.player2_account
-            .total_winnings.saturating_add(p2_win as i64).saturating_sub(stake);
-        accounts.player1_account.total_winnings = accounts.player1_account
-            .total_winnings.saturating_sub(stake);
-        accounts.player2_balance.balance = accounts.player2_balance.balance.saturating_add(p2_win);
+    let player1_score = poker::find_best_hand(&hands[0], &board).1;
+    let player2_score = poker::find_best_hand(&hands[1], &board).1;
+    
+    // Determine winner; the pot stays in the game as chips for the next hand
+    if player1_score > player2_score {
+        game.hand.winner = Some(0);
+        game.hand.winning_hand_rank = Some(poker::hand_rank(player1_score));
+        game.award_pot(0)?;
+    } else if player2_score > player1_score {
+        game.hand.winner = Some(1);
+        game.hand.winning_hand_rank = Some(poker::hand_rank(player2_score));
+        game.award_pot(1)?;
     } else {
-        // Split pot
-        accounts.player1_account.total_winnings = accounts.player1_account
-            .total_winnings.saturating_add(p1_win as i64).saturating_sub(stake);
-        accounts.player2_account.total_winnings = accounts.player2_account
-            .total_winnings.saturating_add(p2_win as i64).saturating_sub(stake);
-        accounts.player1_balance.balance = accounts.player1_balance.balance.saturating_add(p1_win);
-        accounts.player2_balance.balance = accounts.player2_balance.balance.saturating_add(p2_win);
+        game.hand.winner = None;
+        game.hand.winning_hand_rank = Some(poker::hand_rank(player1_score));
+        game.split_pot();
+    }
+    
+    game.hand.stage = HandStage::Complete;
+    game.mark_action(&clock);
+    
+    // Update stats
+    ctx.accounts.player1_account.total_hands_played += 1;
+    ctx.accounts.player2_account.total_hands_played += 1;
+    match game.hand.winner {
+        Some(0) => ctx.accounts.player1_account.total_hands_won += 1,
+        Some(_) => ctx.accounts.player2_account.total_hands_won += 1,
+        None => {}
     }
     
     Ok(())
 }
 
+/// Compatibility alias for resolve_hand
+pub fn resolve_game(
+    ctx: Context<ResolveGame>,
+    pocket_cards: [u8; 2],
+    revealed_cards: CardMultiproof,
+    showdown_proof: Option<ZkProof>,
+    pocket_shares: Vec<PocketCardShares>,
+) -> Result<()> {
+    resolve_hand(ctx, pocket_cards, revealed_cards, showdown_proof, pocket_shares)
+}
+
 #[derive(Accounts)]
 pub struct ResolveGame<'info> {
     #[account(mut)]
-    pub game_state: Box<Account<'info, GameState>>,
+    pub game: Box<Account<'info, Game>>,
     
     #[account(
         mut,
-        seeds = [b"player", game_state.player1.as_ref()],
+        seeds = [b"player", game.players[0].as_ref()],
         bump = player1_account.bump
     )]
     pub player1_account: Account<'info, PlayerAccount>,
     
     #[account(
         mut,
-        seeds = [b"player", game_state.player2.as_ref()],
+        seeds = [b"player", game.players[1].as_ref()],
         bump = player2_account.bump
     )]
     pub player2_account: Account<'info, PlayerAccount>,
     
-    #[account(
-        mut,
-        seeds = [b"balance", game_state.player1.as_ref()],
-        bump = player1_balance.bump
-    )]
-    pub player1_balance: Account<'info, PlayerBalance>,
+    /// Only needed in Paillier matches that pinned a published showdown key
+    pub verifying_key: Option<Account<'info, VerifyingKeyAccount>>,
     
-    #[account(
-        mut,
-        seeds = [b"balance", game_state.player2.as_ref()],
-        bump = player2_balance.bump
-    )]
-    pub player2_balance: Account<'info, PlayerBalance>,
+    pub player: Signer<'info>,
+}
+
+/// Claim timeout win or dispute an optimistically accepted proof
+/// With `DisputedAction::None` this is a timeout claim: the winner receives the pot and
+/// the opponent's bond (penalty) as chips.
+/// Otherwise the opponent's stored proof for the disputed action is verified on-chain:
+/// - Invalid proof: the accused forfeits the pot and their bond to the challenger
+/// - Valid proof: the challenge was frivolous, the challenger's bond goes to the accused
+///   and the hand resumes where it was
+///
+/// A card decryption without a stored proof is checked with a VerificationJob instead,
+/// or with a BisectionGame for `CardDecryptionBisection`: the hand stays in Dispute until
+/// the check is opened by the accused and run to completion.
+pub fn claim_timeout(ctx: Context<ClaimTimeout>, disputed_action: DisputedAction) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let clock = Clock::get()?;
+    let player = ctx.accounts.player.key();
     
-    #[account(
-        mut,
-        constraint = game_vault.key() == game_state.token_vault
-    )]
-    pub game_vault: Account<'info, TokenAccount>,
+    require!(
+        game.game_status == GameStatus::Active,
+        PokerError::InvalidGameStage
+    );
+    require!(
+        game.current_hand_id > 0 && game.hand.stage != HandStage::Complete,
+        PokerError::InvalidGameStage
+    );
     
-    #[account(mut)]
-    pub program_vault: Account<'info, TokenAccount>,
+    let claimer_index = game.get_player_index(&player)?;
     
-    pub player: Signer<'info>,
+    if disputed_action == DisputedAction::None {
+        return claim_action_timeout(game, claimer_index, &clock);
+    }
     
-    pub token_program: Program<'info, Token>,
+    require!(!game.hand.dispute_active, PokerError::DisputeInProgress);
+    
+    // The challenger's bond is what they lose if the proof turns out to be valid
+    require!(
+        game.player_bonds[claimer_index as usize] > 0,
+        PokerError::InsufficientBond
+    );
+    
+    let stored = match disputed_action.proof_type() {
+        // Only the dealer's shares carry a proof; a non-dealer's decryption is checked
+        // with a VerificationJob instead
+        Some(ProofType::CardDecryption { .. }) if claimer_index == game.hand.dealer_index => None,
+        proof_type => proof_type.and_then(|proof_type| game.find_stored_proof(proof_type).cloned()),
+    };
+    let stored = match stored {
+        Some(stored) => stored,
+        None => return open_decryption_dispute(game, claimer_index, disputed_action, &clock),
+    };
+    let accused_index = stored.submitter_index;
+    require!(accused_index != claimer_index, PokerError::CannotDisputeOwnProof);
+    
+    // A missing or wrong key account fails the claim rather than counting against anyone
+    let vk = game.verifying_key(stored.proof_type.circuit(), ctx.accounts.verifying_key.as_deref())?;
+    
+    game.enter_dispute(claimer_index, disputed_action);
+    
+    // Any verification failure (malformed proof included) is the accused's fault
+    if zk::verify_stored_proof(game, &stored, &vk).is_err() {
+        msg!("Disputed proof is invalid - slashing player {}", accused_index);
+        game.forfeit_hand(claimer_index, accused_index)?;
+    } else {
+        msg!("Disputed proof is valid - penalising challenger {}", claimer_index);
+        game.penalise_challenger(claimer_index, accused_index)?;
+    }
+    
+    game.exit_dispute();
+    game.mark_action(&clock);
+    
+    Ok(())
 }
 
-/// Claim timeout win if opponent doesn't act
-/// Winner receives the pot + their bond back + opponent's bond (penalty)
-pub fn claim_timeout(ctx: Context<ClaimTimeout>) -> Result<()> {
-    let game = &mut ctx.accounts.game_state;
-    let clock = Clock::get()?;
-    let player = ctx.accounts.player.key();
+/// Dispute a decryption share that came without a proof
+/// The accused has until the action deadline to open a VerificationJob or BisectionGame
+/// by revealing their Paillier secret, otherwise the challenger can claim the timeout.
+/// Only Paillier matches settle decryptions this way.
+fn open_decryption_dispute(
+    game: &mut Game,
+    challenger_index: u8,
+    disputed_action: DisputedAction,
+    clock: &Clock,
+) -> Result<()> {
+    require!(
+        game.card_backend == CardBackend::Paillier,
+        PokerError::UnsupportedCardBackend
+    );
+    let card_index = match disputed_action {
+        DisputedAction::CardDecryption { card_index }
+        | DisputedAction::CardDecryptionBisection { card_index } => card_index,
+        _ => return err!(PokerError::NoProofToDispute),
+    };
+    // The accused must already have decrypted the card, or they would have nothing to defend
+    require!(
+        game.has_submitted_decryption(1 - challenger_index, card_index),
+        PokerError::NoProofToDispute
+    );
+    
+    game.enter_dispute(challenger_index, disputed_action);
+    game.hand.current_turn_index = 1 - challenger_index;
+    game.mark_action(clock);
     
+    Ok(())
+}
+
+/// Award the hand to the claimer when the opponent failed to act before the deadline
+fn claim_action_timeout(game: &mut Game, claimer_index: u8, clock: &Clock) -> Result<()> {
     // Check that timeout has been reached
-    let elapsed = clock.unix_timestamp - game.last_action_at;
     require!(
-        elapsed > game.action_timeout,
+        game.is_timeout_exceeded(clock),
         PokerError::TimeoutNotReached
     );
     
-    // Verify it's not the claiming player's turn (or their reveal deadline)
-    // In reveal stages, check against reveal_deadline
-    let is_timeout = match game.stage {
-        GameStage::AwaitingPlayer2FlopShare |
-        GameStage::AwaitingPlayer2TurnShare |
-        GameStage::AwaitingPlayer2RiverShare |
-        GameStage::AwaitingPlayer2ShowdownReveal => {
-            clock.unix_timestamp > game.reveal_deadline
-        }
-        _ => {
-            !game.is_player_turn(&player) && elapsed > game.action_timeout
-        }
+    // At showdown the player who has not revealed is stalling,
+    // otherwise it is whoever is expected to act next (including during a dispute)
+    let offender_index = if game.hand.stage == HandStage::Showdown {
+        require!(
+            game.hand.player_revealed_showdown[claimer_index as usize],
+            PokerError::CannotClaimOwnTimeout
+        );
+        1 - claimer_index
+    } else {
+        game.hand.current_turn_index
     };
     
-    require!(is_timeout, PokerError::TimeoutNotReached);
+    require!(
+        offender_index != claimer_index,
+        PokerError::CannotClaimOwnTimeout
+    );
     
     // Award win to the player who didn't timeout
-    game.winner = Some(player);
+    game.forfeit_hand(claimer_index, offender_index)?;
+    game.exit_dispute();
+    game.mark_action(clock);
     
-    // Winner gets pot + their bond + opponent's bond (as penalty)
-    let is_player1 = player == game.player1;
-    let winner_amount = game.pot + game.player1_bond + game.player2_bond;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimTimeout<'info> {
+    #[account(mut)]
+    pub game: Box<Account<'info, Game>>,
+    
+    /// Key for the disputed proof's circuit, if the game pinned a published version
+    pub verifying_key: Option<Account<'info, VerifyingKeyAccount>>,
+    
+    pub player: Signer<'info>,
+}
+
+/// Leave the game and settle the match
+/// Can only be called between hands. Each player's stack and bond are credited to their
+/// balance, the tokens leave the game vault in a single transfer and the vault is closed.
+pub fn leave_game(ctx: Context<LeaveGame>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player = ctx.accounts.player.key();
     
-    if is_player1 {
-        ctx.accounts.player1_balance.balance = ctx.accounts.player1_balance.balance
-            .saturating_add(winner_amount);
+    require!(
+        game.game_status != GameStatus::Concluded,
+        PokerError::GameConcluded
+    );
+    
+    // Only players in this game can end it
+    game.get_player_index(&player)?;
+    
+    require!(
+        matches!(
+            game.hand.stage,
+            HandStage::Complete | HandStage::WaitingForHandCreation
+        ),
+        PokerError::HandInProgress
+    );
+    require!(game.hand.pot == 0, PokerError::HandInProgress);
+    
+    let payouts = [
+        game.player_stacks[0] + game.player_bonds[0],
+        game.player_stacks[1] + game.player_bonds[1],
+    ];
+    let total_amount = payouts[0] + payouts[1];
+    // Tokens sent to the vault from outside the game are swept along with the payouts,
+    // so a donation can't keep the vault from closing
+    let vault_amount = ctx.accounts.game_vault.amount;
+    require!(
+        vault_amount >= total_amount,
+        PokerError::VaultBalanceMismatch
+    );
+    
+    // Credit player balances
+    ctx.accounts.player1_balance.balance = ctx.accounts.player1_balance.balance
+        .checked_add(payouts[0])
+        .ok_or(PokerError::InvalidDepositAmount)?;
+    if let Some(player2_balance) = ctx.accounts.player2_balance.as_mut() {
+        require!(
+            player2_balance.authority == game.players[1],
+            PokerError::InvalidPlayerBalance
+        );
+        player2_balance.balance = player2_balance.balance
+            .checked_add(payouts[1])
+            .ok_or(PokerError::InvalidDepositAmount)?;
     } else {
-        ctx.accounts.player2_balance.balance = ctx.accounts.player2_balance.balance
-            .saturating_add(winner_amount);
+        // Only a game nobody joined can be settled without the second balance
+        require!(payouts[1] == 0, PokerError::InvalidPlayerBalance);
     }
     
-    game.stage = GameStage::Finished;
+    game.player_stacks = [0, 0];
+    game.player_bonds = [0, 0];
+    game.game_status = GameStatus::Concluded;
+    
+    // Move the tokens back to the program vault in one transfer, then close the game vault
+    let game_id = game.game_id;
+    let creator_key = game.players[0];
+    let vault_bump = game.vault_bump;
+    let seeds = &[
+        b"game_vault".as_ref(),
+        creator_key.as_ref(),
+        &game_id.to_le_bytes(),
+        &[vault_bump],
+    ];
+    let signer = &[&seeds[..]];
+    
+    if vault_amount > 0 {
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.game_vault.to_account_info(),
+                to: ctx.accounts.program_vault.to_account_info(),
+                authority: ctx.accounts.game_vault.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(cpi_ctx, vault_amount)?;
+    }
+    
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.game_vault.to_account_info(),
+            destination: ctx.accounts.creator.to_account_info(),
+            authority: ctx.accounts.game_vault.to_account_info(),
+        },
+        signer,
+    );
+    token::close_account(cpi_ctx)?;
     
     Ok(())
 }
 
 #[derive(Accounts)]
-pub struct ClaimTimeout<'info> {
+pub struct LeaveGame<'info> {
     #[account(mut)]
-    pub game_state: Box<Account<'info, GameState>>,
+    pub game: Box<Account<'info, Game>>,
     
     #[account(
         mut,
-        seeds = [b"balance", game_state.player1.as_ref()],
+        seeds = [b"balance", game.players[0].as_ref()],
         bump = player1_balance.bump
     )]
     pub player1_balance: Account<'info, PlayerBalance>,
     
+    /// Omitted when the game is settled before anyone joined
     #[account(
         mut,
-        seeds = [b"balance", game_state.player2.as_ref()],
+        seeds = [b"balance", game.players[1].as_ref()],
         bump = player2_balance.bump
     )]
-    pub player2_balance: Account<'info, PlayerBalance>,
+    pub player2_balance: Option<Account<'info, PlayerBalance>>,
+    
+    #[account(
+        mut,
+        constraint = game_vault.key() == game.token_vault
+    )]
+    pub game_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = program_vault.owner == program_vault_authority.key(),
+        constraint = program_vault.mint == game_vault.mint
+    )]
+    pub program_vault: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the program vault
+    #[account(
+        seeds = [b"program_vault"],
+        bump
+    )]
+    pub program_vault_authority: AccountInfo<'info>,
+    
+    /// CHECK: Game creator, receives the vault's rent on close
+    #[account(
+        mut,
+        address = game.players[0]
+    )]
+    pub creator: AccountInfo<'info>,
     
     pub player: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
 }
-
@@ -4,22 +4,19 @@ use crate::state::*;
 use crate::errors::*;
 
 /// Create a new game
+/// Player 0 funds their stack, posts their card key (which selects Paillier or ElGamal
+/// for the match) and picks the deck Merkle hash and when reshuffles are verified
 pub fn create_game(
     ctx: Context<CreateGame>,
     stake_amount: u64,
-    player1_ephemeral_pubkey: EphemeralPubkey,
-    deck_merkle_root: [u8; 32],
+    card_pk: CardPublicKey,
     game_id: u64,
+    invited_opponent: Option<Pubkey>,
+    merkle_hash: MerkleHash,
+    shuffle_verification: ShuffleVerification,
 ) -> Result<()> {
     require!(stake_amount > 0, PokerError::InvalidBetAmount);
-    require!(
-        player1_ephemeral_pubkey.data != [0u8; 32],
-        PokerError::InvalidEphemeralKey
-    );
-    require!(
-        deck_merkle_root != [0u8; 32],
-        PokerError::InvalidCommitment
-    );
+    ctx.accounts.vk_registry.require_published()?;
     
     // Bond amount (10% of stake)
     let bond_amount = stake_amount / 10;
@@ -52,87 +49,48 @@ pub fn create_game(
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
     token::transfer(cpi_ctx, total_amount)?;
     
-    let game = &mut ctx.accounts.game_state;
+    let game = &mut ctx.accounts.game;
     let clock = Clock::get()?;
     
-    // Initialize game state
+    // Match state
+    game.players = [ctx.accounts.player1.key(), Pubkey::default()];
+    game.card_backend = card_pk.backend();
+    game.paillier_pks = [PaillierPublicKey::default(), PaillierPublicKey::default()];
+    game.elgamal_pks = [[0u8; 32]; 2];
+    game.set_card_key(0, card_pk)?;
+    game.player_stacks = [stake_amount, 0];
+    game.player_bonds = [bond_amount, 0];
     game.game_id = game_id;
-    game.player1 = ctx.accounts.player1.key();
-    game.player2 = Pubkey::default();
+    game.current_hand_id = 0;
+    game.game_status = GameStatus::Pending;
     game.token_vault = ctx.accounts.game_vault.key();
     game.vault_bump = *ctx.bumps.get("game_vault").unwrap();
-    game.stake_amount = stake_amount;
-    game.pot = 0;
-    game.player1_current_bet = 0;
-    game.player2_current_bet = 0;
-    game.player1_stack = stake_amount;
-    game.player2_stack = 0;
-    
-    // Bonds
-    game.player1_bond = bond_amount;
-    game.player2_bond = 0;
-    
-    // Ephemeral keys
-    game.player1_ephemeral_pubkey = player1_ephemeral_pubkey;
-    game.player2_ephemeral_pubkey = EphemeralPubkey::default();
-    
-    // Store Merkle root of Player 1's singly-encrypted deck
-    game.deck_merkle_root = deck_merkle_root;
-    
-    // Initialize encrypted cards (all zero, will be set when player 2 joins)
-    game.encrypted_cards = [EncryptedCard::default(); 9];
-    
-    // Initialize decryption shares
-    game.player1_flop_shares = [EncryptedCard::default(); 3];
-    game.player1_turn_share = EncryptedCard::default();
-    game.player1_river_share = EncryptedCard::default();
-    
-    // Initialize hands and community cards
-    game.player1_hand = [0u8; 2];
-    game.player2_hand = [0u8; 2];
-    game.community_cards = [0u8; 5];
-    game.community_cards_revealed = 0;
-    
-    // Game state
-    game.stage = GameStage::WaitingForPlayer2;
-    game.current_player = 0;
-    game.dealer_button = 1; // Player 1 is dealer
-    game.last_action = PlayerActionType::None;
     
     // Blinds (configurable, but standard is SB=1, BB=2 in chips)
     game.small_blind = stake_amount / 100; // 1% of stake
     game.big_blind = stake_amount / 50; // 2% of stake
     
-    // Player states
-    game.player1_folded = false;
-    game.player2_folded = false;
-    game.player1_all_in = false;
-    game.player2_all_in = false;
-    game.player1_revealed_hand = false;
-    game.player2_revealed_hand = false;
-    
-    // Timing
-    game.created_at = clock.unix_timestamp;
-    game.last_action_at = clock.unix_timestamp;
     game.action_timeout = 60; // 60 seconds per action
-    game.reveal_deadline = 0;
-    
-    // Result
-    game.winner = None;
-    game.winning_hand_rank = None;
+    game.merkle_hash = merkle_hash;
+    game.shuffle_verification = shuffle_verification;
+    game.vk_versions = ctx.accounts.vk_registry.current_versions;
+    game.invited_opponent = invited_opponent;
+    game.bump = *ctx.bumps.get("game").unwrap();
     
-    game.bump = *ctx.bumps.get("game_state").unwrap();
+    // No hand is dealt until the second player joins and start_new_hand is called
+    game.hand = HandState::default();
+    game.last_action_timestamp = clock.unix_timestamp;
     
     Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(stake_amount: u64, player1_ephemeral_pubkey: EphemeralPubkey, deck_merkle_root: [u8; 32], game_id: u64)]
+#[instruction(stake_amount: u64, card_pk: CardPublicKey, game_id: u64)]
 pub struct CreateGame<'info> {
     #[account(
         init,
         payer = player1,
-        space = GameState::LEN,
+        space = Game::INIT_LEN,
         seeds = [
             b"game",
             player1.key().as_ref(),
@@ -140,7 +98,7 @@ pub struct CreateGame<'info> {
         ],
         bump
     )]
-    pub game_state: Box<Account<'info, GameState>>,
+    pub game: Box<Account<'info, Game>>,
     
     #[account(
         mut,
@@ -175,6 +133,10 @@ pub struct CreateGame<'info> {
     
     pub usdc_mint: Account<'info, Mint>,
     
+    /// Current verifying key versions, pinned for the whole match
+    #[account(seeds = [b"vk_registry"], bump = vk_registry.bump)]
+    pub vk_registry: Account<'info, VerifyingKeyRegistry>,
+    
     #[account(mut)]
     pub player1: Signer<'info>,
     
@@ -184,102 +146,39 @@ pub struct CreateGame<'info> {
 }
 
 /// Player 2 joins the game
-/// Requires Merkle proofs for each card to prove they came from Player 1's committed deck
+/// Funds their stack and posts their card key for the match's backend; hands are then
+/// dealt with start_new_hand, create_hand and join_hand
 pub fn join_game(
     ctx: Context<JoinGame>,
-    player2_ephemeral_pubkey: EphemeralPubkey,
-    encrypted_cards: [EncryptedCard; 9],
-    _singly_encrypted_cards: [EncryptedCard; 9],
-    merkle_proofs: Vec<MerkleProof>,
+    card_pk: CardPublicKey,
 ) -> Result<()> {
-    require!(
-        player2_ephemeral_pubkey.data != [0u8; 32],
-        PokerError::InvalidEphemeralKey
-    );
-    
-    require!(
-        merkle_proofs.len() == 9,
-        PokerError::InvalidEncryptedCards
-    );
-    
-    // Validate that encrypted cards are not all zeros
-    for card in encrypted_cards.iter() {
-        require!(
-            card.data != [0u8; 32],
-            PokerError::InvalidEncryptedCards
-        );
-    }
-    
-    let game = &mut ctx.accounts.game_state;
+    let game = &mut ctx.accounts.game;
     let clock = Clock::get()?;
+    let player2 = ctx.accounts.player2.key();
     
     require!(
-        game.stage == GameStage::WaitingForPlayer2,
+        game.game_status == GameStatus::Pending,
         PokerError::InvalidGameStage
     );
     
     require!(
-        game.player2 == Pubkey::default(),
+        game.players[1] == Pubkey::default(),
         PokerError::GameAlreadyFull
     );
     
     require!(
-        ctx.accounts.player2.key() != game.player1,
+        player2 != game.players[0],
         PokerError::CannotJoinOwnGame
     );
     
-    // OPTIMISTIC VERIFICATION MODEL:
-    // 
-    // On-chain verification is skipped due to Solana constraints:
-    // - Merkle proofs (1.7KB) exceed transaction size limit (1.2KB)
-    // - Re-encryption verification exhausts heap limit (32KB)
-    //
-    // Instead, we use an optimistic approach:
-    // 1. Player 2 submits encrypted cards + proof data off-chain
-    // 2. Player 1 verifies the proof CLIENT-SIDE before continuing
-    // 3. If verification fails, Player 1 can claim_timeout to get stake + Player 2's bond
-    // 4. Player 2's bond incentivizes honest behavior
-    //
-    // Security:
-    // - Player 2 risks losing their bond if they cheat
-    // - Player 1 can immediately detect cheating and exit
-    // - No on-chain computation needed
-    // - Same security model as optimistic rollups
-    
-    // // Verify Merkle proofs for all 9 cards
-    // // This proves that Player 2 selected cards from Player 1's committed deck
-    // for i in 0..9 {
-    //     let proof = &merkle_proofs[i];
-    //     let singly_encrypted = &singly_encrypted_cards[i];
-        
-    //     // Verify the Merkle proof
-    //     let is_valid = GameState::verify_merkle_proof(
-    //         singly_encrypted,
-    //         &proof.proof,
-    //         &game.deck_merkle_root,
-    //         proof.index as usize,
-    //     );
-        
-    //     require!(is_valid, PokerError::CardVerificationFailed);
-        
-    //     // Verify that the doubly-encrypted card is the singly-encrypted card
-    //     // encrypted with Player 2's public key
-    //     // We'll do a simplified check: re-encrypt the singly-encrypted card
-    //     // and verify it matches the doubly-encrypted card
-    //     let re_encrypted = GameState::encrypt_card_bytes(
-    //         &singly_encrypted.data,
-    //         &player2_ephemeral_pubkey,
-    //     );
-        
-    //     require!(
-    //         re_encrypted.data == encrypted_cards[i].data,
-    //         PokerError::CardVerificationFailed
-    //     );
-    // }
+    if let Some(invited) = game.invited_opponent {
+        require!(player2 == invited, PokerError::NotInvited);
+    }
     
     // Bond amount (10% of stake)
-    let bond_amount = game.stake_amount / 10;
-    let total_amount = game.stake_amount + bond_amount;
+    let stake_amount = game.player_stacks[0];
+    let bond_amount = game.player_bonds[0];
+    let total_amount = stake_amount + bond_amount;
     
     // Check player has sufficient balance
     let player_balance = &mut ctx.accounts.player2_balance;
@@ -309,42 +208,25 @@ pub fn join_game(
     token::transfer(cpi_ctx, total_amount)?;
     
     // Set player 2 info
-    game.player2 = ctx.accounts.player2.key();
-    game.player2_ephemeral_pubkey = player2_ephemeral_pubkey;
-    game.player2_stack = game.stake_amount;
-    game.player2_bond = bond_amount;
-    
-    // Store the 9 doubly-encrypted cards
-    game.encrypted_cards = encrypted_cards;
-    
-    // Post blinds (in heads-up, button is SB and acts first pre-flop)
-    if game.dealer_button == 1 {
-        game.player1_current_bet = game.small_blind;
-        game.player2_current_bet = game.big_blind;
-        game.player1_stack = game.player1_stack.saturating_sub(game.small_blind);
-        game.player2_stack = game.player2_stack.saturating_sub(game.big_blind);
-        game.pot = game.small_blind + game.big_blind;
-        game.current_player = 1; // SB acts first pre-flop
-    } else {
-        game.player2_current_bet = game.small_blind;
-        game.player1_current_bet = game.big_blind;
-        game.player2_stack = game.player2_stack.saturating_sub(game.small_blind);
-        game.player1_stack = game.player1_stack.saturating_sub(game.big_blind);
-        game.pot = game.small_blind + game.big_blind;
-        game.current_player = 2;
-    }
-    
-    // Move to pre-flop betting
-    game.stage = GameStage::PreFlopBetting;
-    game.last_action_at = clock.unix_timestamp;
+    game.players[1] = player2;
+    game.set_card_key(1, card_pk)?;
+    game.player_stacks[1] = stake_amount;
+    game.player_bonds[1] = bond_amount;
+    game.game_status = GameStatus::Active;
+    game.last_action_timestamp = clock.unix_timestamp;
     
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct JoinGame<'info> {
-    #[account(mut)]
-    pub game_state: Box<Account<'info, GameState>>,
+    #[account(
+        mut,
+        realloc = Game::LEN,
+        realloc::payer = player2,
+        realloc::zero = false
+    )]
+    pub game: Box<Account<'info, Game>>,
     
     #[account(
         mut,
@@ -355,7 +237,7 @@ pub struct JoinGame<'info> {
     
     #[account(
         mut,
-        constraint = game_vault.key() == game_state.token_vault
+        constraint = game_vault.key() == game.token_vault
     )]
     pub game_vault: Account<'info, TokenAccount>,
     
@@ -372,211 +254,137 @@ pub struct JoinGame<'info> {
     #[account(mut)]
     pub player2: Signer<'info>,
     
+    pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
 }
 
 /// Reveal community cards - Two step process
-/// Step 1: Player 1 submits their decryption shares
-/// Step 2: Player 2 submits their decryption shares and plaintext cards, which are then verified
+/// Step 1: The dealer submits their decryption shares
+/// Step 2: The non-dealer submits the plaintext cards
+/// Both steps include the doubly-encrypted card digests with a Merkle multiproof.
+/// In Paillier matches the dealer stores a ProveCorrectDecryption proof per share, checked
+/// only if the non-dealer disputes it. In ElGamal matches both players' shares come with
+/// Chaum–Pedersen proofs, so each step is checked immediately and step 2 decrypts the
+/// cards on-chain.
+/// After an all-in the runout reveals every remaining board card in the same two steps.
 pub fn reveal_community_cards(
     ctx: Context<RevealCommunityCards>,
     decryption_shares: Vec<EncryptedCard>,
     plaintext_cards: Option<Vec<u8>>,
+    revealed_cards: CardMultiproof,
+    share_proofs: Vec<DecryptionShareProof>,
+    decryption_proofs: Vec<ZkProof>,
 ) -> Result<()> {
-    let game = &mut ctx.accounts.game_state;
+    let game = &mut ctx.accounts.game;
     let player = ctx.accounts.player.key();
     let clock = Clock::get()?;
     
-    let is_player1 = player == game.player1;
-    
     // Determine which street we're revealing based on current stage
-    match game.stage {
-        // FLOP REVEAL
-        GameStage::AwaitingFlopReveal => {
-            require!(is_player1, PokerError::NotYourTurn);
-            require!(
-                decryption_shares.len() == 3,
-                PokerError::MissingDecryptionShares
-            );
-            
-            // Store Player 1's decryption shares for the flop
-            game.player1_flop_shares = [
-                decryption_shares[0],
-                decryption_shares[1],
-                decryption_shares[2],
-            ];
-            
-            // Set deadline for Player 2 to respond
-            game.reveal_deadline = clock.unix_timestamp + game.action_timeout;
-            game.stage = GameStage::AwaitingPlayer2FlopShare;
-            game.last_action_at = clock.unix_timestamp;
-        }
-        
-        GameStage::AwaitingPlayer2FlopShare => {
-            require!(!is_player1, PokerError::NotYourTurn);
-            require!(
-                decryption_shares.len() == 3,
-                PokerError::MissingDecryptionShares
-            );
-            require!(
-                plaintext_cards.is_some() && plaintext_cards.as_ref().unwrap().len() == 3,
-                PokerError::InvalidCommunityCards
-            );
-            
-            let plaintext = plaintext_cards.unwrap();
-            
-            // OPTIMISTIC VERIFICATION MODEL:
-            // Card verification using BigUint modpow is too expensive (1.2M CU for 3 cards!)
-            // Instead, we trust the players and allow off-chain verification:
-            // - Player 1 can verify off-chain that Player 2's shares are valid
-            // - If Player 2 cheated, Player 1 can claim_timeout to get stake + bond
-            // - This mirrors the optimistic verification we use in join_game
-            msg!("⚠️  Using optimistic verification - verifying off-chain for efficiency");
-            // // Verify each card
-            // let encrypted_flop = game.get_flop_encrypted_cards();
-            // for i in 0..3 {
-            //     require!(
-            //         game.verify_card(plaintext[i], &encrypted_flop[i]),
-            //         PokerError::CardVerificationFailed
-            //     );
-            // }
-            
-            // Store Player 2's decryption shares (for potential disputes)
-            // Note: These are stored but not verified on-chain due to CU constraints
-            
-            // Store revealed plaintext cards
-            game.community_cards[0] = plaintext[0];
-            game.community_cards[1] = plaintext[1];
-            game.community_cards[2] = plaintext[2];
-            game.community_cards_revealed = 3;
-            
-            // Move to post-flop betting
-            // In heads-up, big blind acts first post-flop
-            game.current_player = if game.dealer_button == 1 { 2 } else { 1 };
-            game.player1_current_bet = 0;
-            game.player2_current_bet = 0;
-            game.stage = GameStage::PostFlopBetting;
-            game.last_action = PlayerActionType::None;
-            game.last_action_at = clock.unix_timestamp;
-        }
+    let (card_indices, first_position): (&[u8], usize) = match game.hand.stage {
+        HandStage::AwaitingFlopReveal => (&FLOP_CARD_INDICES, 0),
+        HandStage::AwaitingTurnReveal => (&[TURN_CARD_INDEX], 3),
+        HandStage::AwaitingRiverReveal => (&[RIVER_CARD_INDEX], 4),
+        HandStage::AwaitingRunout => (game.undealt_board_indices(), game.dealt_board_cards()),
+        _ => return Err(PokerError::InvalidGameStage.into()),
+    };
+    
+    require!(game.is_player_turn(&player)?, PokerError::NotYourTurn);
+    
+    // The revealed positions must come from the committed doubly-encrypted deck
+    game.verify_revealed_cards(card_indices, &revealed_cards)?;
+    
+    if game.find_revealed_card(card_indices[0]).is_none() {
+        require!(
+            decryption_shares.len() == card_indices.len(),
+            PokerError::MissingDecryptionShares
+        );
         
-        // TURN REVEAL
-        GameStage::AwaitingTurnReveal => {
-            require!(is_player1, PokerError::NotYourTurn);
-            require!(
-                decryption_shares.len() == 1,
-                PokerError::MissingDecryptionShares
-            );
-            
-            // Store Player 1's decryption share for the turn
-            game.player1_turn_share = decryption_shares[0];
-            
-            // Set deadline for Player 2 to respond
-            game.reveal_deadline = clock.unix_timestamp + game.action_timeout;
-            game.stage = GameStage::AwaitingPlayer2TurnShare;
-            game.last_action_at = clock.unix_timestamp;
+        // Step 1: store the dealer's decryption shares
+        match game.card_backend {
+            CardBackend::Paillier => {
+                require!(
+                    decryption_proofs.len() == card_indices.len(),
+                    PokerError::MissingProof
+                );
+                for share in decryption_shares.iter() {
+                    game.validate_decryption_share(&share.data)?;
+                }
+                let dealer_index = game.hand.dealer_index;
+                for (card_index, proof) in card_indices.iter().zip(decryption_proofs) {
+                    let proof_type = ProofType::CardDecryption { card_index: *card_index };
+                    game.store_proof(proof_type, dealer_index, proof, &clock)?;
+                }
+            }
+            CardBackend::ElGamalRistretto => {
+                let dealer_index = game.hand.dealer_index;
+                game.verify_decryption_shares(dealer_index, &revealed_cards, &decryption_shares, &share_proofs)?;
+            }
         }
-        
-        GameStage::AwaitingPlayer2TurnShare => {
-            require!(!is_player1, PokerError::NotYourTurn);
-            require!(
-                decryption_shares.len() == 1,
-                PokerError::MissingDecryptionShares
-            );
-            require!(
-                plaintext_cards.is_some() && plaintext_cards.as_ref().unwrap().len() == 1,
-                PokerError::InvalidCommunityCards
-            );
-            
-            let plaintext = plaintext_cards.unwrap();
-            
-            // OPTIMISTIC VERIFICATION: Skip expensive on-chain verification (see flop comment)
-            msg!("⚠️  Using optimistic verification - verifying off-chain for efficiency");
-            // let encrypted_turn = game.get_turn_encrypted_card();
-            // // Verify the card
-            // require!(
-            //     game.verify_card(plaintext[0], &encrypted_turn),
-            //     PokerError::CardVerificationFailed
-            // );
-            
-            // Store revealed plaintext card
-            game.community_cards[3] = plaintext[0];
-            game.community_cards_revealed = 4;
-            
-            // Move to post-turn betting
-            game.current_player = if game.dealer_button == 1 { 2 } else { 1 };
-            game.player1_current_bet = 0;
-            game.player2_current_bet = 0;
-            game.stage = GameStage::PostTurnBetting;
-            game.last_action = PlayerActionType::None;
-            game.last_action_at = clock.unix_timestamp;
-        }
-        
-        // RIVER REVEAL
-        GameStage::AwaitingRiverReveal => {
-            require!(is_player1, PokerError::NotYourTurn);
-            require!(
-                decryption_shares.len() == 1,
-                PokerError::MissingDecryptionShares
-            );
-            
-            // Store Player 1's decryption share for the river
-            game.player1_river_share = decryption_shares[0];
-            
-            // Set deadline for Player 2 to respond
-            game.reveal_deadline = clock.unix_timestamp + game.action_timeout;
-            game.stage = GameStage::AwaitingPlayer2RiverShare;
-            game.last_action_at = clock.unix_timestamp;
+        for (card_index, share) in card_indices.iter().zip(decryption_shares) {
+            game.reveal_card(*card_index, PartiallyDecryptedCard { data: share.data })?;
         }
         
-        GameStage::AwaitingPlayer2RiverShare => {
-            require!(!is_player1, PokerError::NotYourTurn);
-            require!(
-                decryption_shares.len() == 1,
-                PokerError::MissingDecryptionShares
-            );
+        // The non-dealer now has to complete the reveal
+        game.switch_turn();
+        game.mark_action(&clock);
+        return Ok(());
+    }
+    
+    // Step 2: the non-dealer completes the reveal
+    let plaintext = match game.card_backend {
+        CardBackend::Paillier => {
+            let plaintext = plaintext_cards.ok_or(PokerError::InvalidCommunityCards)?;
             require!(
-                plaintext_cards.is_some() && plaintext_cards.as_ref().unwrap().len() == 1,
+                plaintext.len() == card_indices.len(),
                 PokerError::InvalidCommunityCards
             );
             
-            let plaintext = plaintext_cards.unwrap();
-            
-            // OPTIMISTIC VERIFICATION: Skip expensive on-chain verification (see flop comment)
+            // OPTIMISTIC VERIFICATION MODEL:
+            // Card verification using BigUint modpow is too expensive (1.2M CU for 3 cards!)
+            // Instead, the decryption is accepted optimistically and can be disputed
+            // through claim_timeout, which checks it over several transactions with a VerificationJob.
             msg!("⚠️  Using optimistic verification - verifying off-chain for efficiency");
-            // let encrypted_river = game.get_river_encrypted_card();
-            // // Verify the card
-            // require!(
-            //     game.verify_card(plaintext[0], &encrypted_river),
-            //     PokerError::CardVerificationFailed
-            // );
-            
-            // Store revealed plaintext card
-            game.community_cards[4] = plaintext[0];
-            game.community_cards_revealed = 5;
-            
-            // Move to post-river betting
-            game.current_player = if game.dealer_button == 1 { 2 } else { 1 };
-            game.player1_current_bet = 0;
-            game.player2_current_bet = 0;
-            game.stage = GameStage::PostRiverBetting;
-            game.last_action = PlayerActionType::None;
-            game.last_action_at = clock.unix_timestamp;
+            plaintext
         }
-        
-        _ => {
-            return Err(PokerError::InvalidGameStage.into());
+        CardBackend::ElGamalRistretto => {
+            // With both shares proven, the cards are decrypted here
+            let non_dealer_index = game.non_dealer_index();
+            let ciphertexts = game.verify_decryption_shares(
+                non_dealer_index,
+                &revealed_cards,
+                &decryption_shares,
+                &share_proofs,
+            )?;
+            let cards = game.decrypt_revealed_cards(card_indices, &ciphertexts, &decryption_shares)?;
+            if let Some(claimed) = plaintext_cards {
+                require!(claimed == cards, PokerError::InvalidCommunityCards);
+            }
+            cards
         }
+    };
+    
+    for (offset, card) in plaintext.iter().enumerate() {
+        require!(*card < DECK_SIZE, PokerError::InvalidCommunityCards);
+        game.finalize_community_card(first_position + offset, *card)?;
     }
     
+    if game.hand.stage == HandStage::AwaitingRunout {
+        // The board is complete after an all-in, so no more betting rounds are played
+        game.hand.stage = HandStage::Showdown;
+        game.hand.current_turn_index = game.hand.dealer_index;
+    } else {
+        // Move to the next betting round; in heads-up, the big blind acts first post-flop
+        game.advance_betting_round();
+    }
+    game.mark_action(&clock);
+    
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct RevealCommunityCards<'info> {
     #[account(mut)]
-    pub game_state: Box<Account<'info, GameState>>,
+    pub game: Box<Account<'info, Game>>,
     
     pub player: Signer<'info>,
 }
-
@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::zk;
+
+/// Start a new hand within an active game
+/// Either player can call this once the previous hand is complete; the dealer rotates
+pub fn start_new_hand(ctx: Context<StartNewHand>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player = ctx.accounts.player.key();
+    let clock = Clock::get()?;
+    
+    require!(
+        game.game_status == GameStatus::Active,
+        PokerError::InvalidGameStage
+    );
+    
+    // Only players in this game can start a hand
+    game.get_player_index(&player)?;
+    
+    // The first hand starts straight after join_game, later hands after the previous one completes
+    require!(
+        game.current_hand_id == 0 || game.hand.stage == HandStage::Complete,
+        PokerError::HandInProgress
+    );
+    
+    game.init_new_hand(&clock);
+    
+    // Both players must be able to cover their blind for this hand
+    let dealer_index = game.hand.dealer_index as usize;
+    let non_dealer_index = game.non_dealer_index() as usize;
+    require!(
+        game.player_stacks[dealer_index] >= game.small_blind
+            && game.player_stacks[non_dealer_index] >= game.big_blind,
+        GameError::InsufficientStack
+    );
+    
+    game.mark_action(&clock);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct StartNewHand<'info> {
+    #[account(mut)]
+    pub game: Box<Account<'info, Game>>,
+    
+    pub player: Signer<'info>,
+}
+
+/// Create the hand (non-dealer)
+/// Commits to the non-dealer's singly-encrypted deck, verifies the deck creation proof and posts blinds
+pub fn create_hand(
+    ctx: Context<CreateHand>,
+    deck_merkle_root: [u8; 32],
+    deck_creation_proof: ZkProof,
+) -> Result<()> {
+    require!(
+        deck_merkle_root != [0u8; 32],
+        PokerError::InvalidCommitment
+    );
+    
+    let game = &mut ctx.accounts.game;
+    let player = ctx.accounts.player.key();
+    let clock = Clock::get()?;
+    
+    require!(
+        game.hand.stage == HandStage::WaitingForHandCreation,
+        PokerError::InvalidGameStage
+    );
+    
+    let player_index = game.get_player_index(&player)?;
+    require!(
+        player_index == game.non_dealer_index(),
+        PokerError::NotYourTurn
+    );
+    
+    // The deck creation proof is mandatory and checked on-chain: it must bind
+    // the committed deck to both players' card keys
+    let vk = game.verifying_key(ProofCircuit::DeckCreation, ctx.accounts.verifying_key.as_deref())?;
+    let card_keys = zk::card_keys_to_field_elements(game);
+    zk::verify_deck_creation_proof(&vk, &deck_creation_proof, &deck_merkle_root, &card_keys)?;
+    
+    game.hand.deck_merkle_root = deck_merkle_root;
+    game.store_proof(ProofType::DeckCreation, player_index, deck_creation_proof, &clock)?;
+    game.post_blinds()?;
+    
+    // The dealer now has to reshuffle and join the hand
+    game.hand.stage = HandStage::WaitingForDealerJoin;
+    game.hand.current_turn_index = game.hand.dealer_index;
+    game.mark_action(&clock);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateHand<'info> {
+    #[account(mut)]
+    pub game: Box<Account<'info, Game>>,
+    
+    /// Only needed when the game pinned a published deck creation key
+    pub verifying_key: Option<Account<'info, VerifyingKeyAccount>>,
+    
+    pub player: Signer<'info>,
+}
+
+/// Join the hand (dealer)
+/// Commits to the reshuffled, doubly-encrypted deck and stores the reshuffle proof, which is
+/// verified here or only when disputed depending on the match's shuffle verification
+pub fn join_hand(
+    ctx: Context<JoinHand>,
+    doubly_encrypted_deck_merkle_root: [u8; 32],
+    reshuffle_proof: ZkProof,
+) -> Result<()> {
+    require!(
+        doubly_encrypted_deck_merkle_root != [0u8; 32],
+        PokerError::InvalidCommitment
+    );
+    
+    let game = &mut ctx.accounts.game;
+    let player = ctx.accounts.player.key();
+    let clock = Clock::get()?;
+    
+    require!(
+        game.hand.stage == HandStage::WaitingForDealerJoin,
+        PokerError::InvalidGameStage
+    );
+    
+    let player_index = game.get_player_index(&player)?;
+    require!(
+        player_index == game.hand.dealer_index,
+        PokerError::NotYourTurn
+    );
+    
+    match game.shuffle_verification {
+        ShuffleVerification::Immediate => {
+            let vk = game.verifying_key(ProofCircuit::Reshuffle, ctx.accounts.verifying_key.as_deref())?;
+            let card_keys = zk::card_keys_to_field_elements(game);
+            zk::verify_reshuffle_proof(
+                &vk,
+                &reshuffle_proof,
+                &game.hand.deck_merkle_root,
+                &doubly_encrypted_deck_merkle_root,
+                &card_keys,
+            )?;
+        }
+        ShuffleVerification::Dispute => {
+            // OPTIMISTIC VERIFICATION MODEL:
+            // The reshuffle proof is stored but not verified. The non-dealer checks it
+            // client-side and can dispute it through claim_timeout.
+        }
+    }
+    game.hand.doubly_encrypted_deck_merkle_root = doubly_encrypted_deck_merkle_root;
+    game.store_proof(ProofType::Reshuffle, player_index, reshuffle_proof, &clock)?;
+    
+    // Move to pre-flop betting (in heads-up, the dealer is SB and acts first pre-flop)
+    game.hand.stage = HandStage::PreFlopBetting;
+    game.hand.current_turn_index = game.hand.dealer_index;
+    game.mark_action(&clock);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct JoinHand<'info> {
+    #[account(mut)]
+    pub game: Box<Account<'info, Game>>,
+    
+    /// Only needed when shuffles are verified immediately with a published key
+    pub verifying_key: Option<Account<'info, VerifyingKeyAccount>>,
+    
+    pub player: Signer<'info>,
+}
@@ -1,12 +1,22 @@
 pub mod player;
 pub mod funds;
 pub mod game_setup;
+pub mod hand_setup;
+pub mod deck_buffer;
 pub mod game_play;
 pub mod game_end;
+pub mod verification;
+pub mod bisection;
+pub mod verifying_keys;
 
 pub use player::*;
 pub use funds::*;
 pub use game_setup::*;
+pub use hand_setup::*;
+pub use deck_buffer::*;
 pub use game_play::*;
 pub use game_end::*;
+pub use verification::*;
+pub use bisection::*;
+pub use verifying_keys::*;
 
@@ -0,0 +1,222 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+/// Open the on-chain check of a disputed card decryption (accused player)
+/// The accused reveals their Paillier secret λ and μ = λ^-1 mod n together with an
+/// R^2 mod n^2 hint.
+pub fn open_verification_job(
+    ctx: Context<OpenVerificationJob>,
+    lambda: Vec<u8>,
+    lambda_inverse: Vec<u8>,
+    montgomery_r2: Vec<u8>,
+) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player = ctx.accounts.player.key();
+    let clock = Clock::get()?;
+    
+    let card_index = match game.hand.disputed_action {
+        DisputedAction::CardDecryption { card_index } => card_index,
+        _ => return err!(PokerError::InvalidGameStage),
+    };
+    require!(
+        game.hand.stage == HandStage::Dispute && game.hand.dispute_active,
+        PokerError::InvalidGameStage
+    );
+    
+    let challenger_index = game.hand.challenger_index;
+    let accused_index = 1 - challenger_index;
+    require!(
+        game.get_player_index(&player)? == accused_index,
+        PokerError::NotYourTurn
+    );
+    
+    let (ciphertext, claim) = disputed_decryption(
+        game,
+        ctx.accounts.deck_buffer.as_deref(),
+        accused_index,
+        card_index,
+    )?;
+    
+    let job = &mut ctx.accounts.verification_job;
+    job.game = game.key();
+    job.hand_id = game.current_hand_id;
+    job.card_index = card_index;
+    job.accused_index = accused_index;
+    job.challenger_index = challenger_index;
+    job.payer = player;
+    job.bump = *ctx.bumps.get("verification_job").unwrap();
+    job.start(
+        &game.paillier_pks[accused_index as usize].n,
+        lambda,
+        &lambda_inverse,
+        &ciphertext,
+        claim,
+        montgomery_r2,
+    )?;
+    
+    if job.status == VerificationStatus::InProgress {
+        // The challenger now has to crank the job to completion
+        game.hand.current_turn_index = challenger_index;
+        game.mark_action(&clock);
+    } else {
+        settle_decryption_dispute(game, job.card_index, job.challenger_index, job.status, &clock)?;
+    }
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OpenVerificationJob<'info> {
+    #[account(mut)]
+    pub game: Box<Account<'info, Game>>,
+    
+    #[account(
+        init,
+        payer = player,
+        space = VerificationJob::LEN,
+        seeds = [
+            b"verification_job",
+            game.key().as_ref(),
+            &game.current_hand_id.to_le_bytes(),
+            &[disputed_card_index(&game.hand.disputed_action)]
+        ],
+        bump
+    )]
+    pub verification_job: Box<Account<'info, VerificationJob>>,
+    
+    /// Only needed when the dealer is accused
+    #[account(
+        seeds = [
+            b"deck_buffer",
+            game.key().as_ref(),
+            &game.current_hand_id.to_le_bytes()
+        ],
+        bump = deck_buffer.bump
+    )]
+    pub deck_buffer: Option<Account<'info, DeckBuffer>>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Advance a verification job by a bounded number of exponent bits
+/// Anyone can crank; once the job completes the dispute is settled.
+pub fn crank_verification(ctx: Context<CrankVerification>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let job = &mut ctx.accounts.verification_job;
+    let clock = Clock::get()?;
+    
+    require!(
+        job.hand_id == game.current_hand_id && game.hand.dispute_active,
+        PokerError::InvalidVerificationJob
+    );
+    
+    job.step(VerificationJob::BITS_PER_CRANK)?;
+    
+    if job.status == VerificationStatus::InProgress {
+        game.mark_action(&clock);
+    } else {
+        settle_decryption_dispute(game, job.card_index, job.challenger_index, job.status, &clock)?;
+    }
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CrankVerification<'info> {
+    #[account(mut)]
+    pub game: Box<Account<'info, Game>>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"verification_job",
+            game.key().as_ref(),
+            &verification_job.hand_id.to_le_bytes(),
+            &[verification_job.card_index]
+        ],
+        bump = verification_job.bump
+    )]
+    pub verification_job: Box<Account<'info, VerificationJob>>,
+    
+    pub cranker: Signer<'info>,
+}
+
+/// Deck position under dispute, used to derive the verification account addresses
+pub fn disputed_card_index(disputed_action: &DisputedAction) -> u8 {
+    match disputed_action {
+        DisputedAction::CardDecryption { card_index }
+        | DisputedAction::CardDecryptionBisection { card_index } => *card_index,
+        _ => u8::MAX,
+    }
+}
+
+/// Ciphertext and claim of the decryption the accused performed
+/// - Dealer: their share is the λ-layer of the doubly-encrypted card from the DeckBuffer
+/// - Non-dealer: the dealer's share decrypts to the revealed community card
+pub(crate) fn disputed_decryption(
+    game: &Game,
+    deck_buffer: Option<&DeckBuffer>,
+    accused_index: u8,
+    card_index: u8,
+) -> Result<(Vec<u8>, DecryptionClaim)> {
+    let share = game
+        .find_revealed_card(card_index)
+        .ok_or(PokerError::MissingDecryptionShares)?
+        .data
+        .clone();
+    
+    if accused_index == game.hand.dealer_index {
+        let deck_buffer = deck_buffer.ok_or(PokerError::InvalidDeckBuffer)?;
+        require!(
+            deck_buffer.hand_id == game.current_hand_id,
+            PokerError::InvalidDeckBuffer
+        );
+        let ciphertext = deck_buffer
+            .card(DeckKind::Reshuffled, card_index)
+            .ok_or(PokerError::InvalidDeckBuffer)?
+            .to_vec();
+        Ok((ciphertext, DecryptionClaim::DealerShare(share)))
+    } else {
+        let position = Game::community_card_position(card_index)
+            .ok_or(GameError::InvalidCardPosition)?;
+        let card = game.hand.community_cards[position]
+            .ok_or(PokerError::InvalidCommunityCards)?;
+        Ok((share, DecryptionClaim::Plaintext(vec![card])))
+    }
+}
+
+/// Settle a decryption dispute once its outcome is known
+pub(crate) fn settle_decryption_dispute(
+    game: &mut Game,
+    card_index: u8,
+    challenger_index: u8,
+    status: VerificationStatus,
+    clock: &Clock,
+) -> Result<()> {
+    require!(
+        game.hand.dispute_active && disputed_card_index(&game.hand.disputed_action) == card_index,
+        PokerError::InvalidVerificationJob
+    );
+    let accused_index = 1 - challenger_index;
+    
+    match status {
+        VerificationStatus::Invalid => {
+            msg!("Decryption is invalid - slashing player {}", accused_index);
+            game.forfeit_hand(challenger_index, accused_index)?;
+        }
+        VerificationStatus::Valid => {
+            msg!("Decryption is valid - penalising challenger {}", challenger_index);
+            game.penalise_challenger(challenger_index, accused_index)?;
+        }
+        VerificationStatus::InProgress => return err!(PokerError::InvalidVerificationJob),
+    }
+    
+    game.exit_dispute();
+    game.mark_action(clock);
+    
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+/// Create the verifying key registry
+/// Only the program's upgrade authority can do this; it becomes the registry admin.
+/// All circuits start at version 0, which has no usable key until one is published.
+pub fn initialize_vk_registry(ctx: Context<InitializeVkRegistry>) -> Result<()> {
+    let registry = &mut ctx.accounts.vk_registry;
+    registry.admin = ctx.accounts.authority.key();
+    registry.current_versions = [0; PROOF_CIRCUIT_COUNT];
+    registry.bump = *ctx.bumps.get("vk_registry").unwrap();
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeVkRegistry<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = VerifyingKeyRegistry::LEN,
+        seeds = [b"vk_registry"],
+        bump
+    )]
+    pub vk_registry: Account<'info, VerifyingKeyRegistry>,
+    
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Zkpoker>,
+    
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ PokerError::NotRegistryAdmin
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Publish a new verifying key for a circuit
+/// The key gets the next version number and becomes current for games created from now
+/// on; games already in progress keep verifying against the version they pinned.
+pub fn publish_verifying_key(
+    ctx: Context<PublishVerifyingKey>,
+    circuit: ProofCircuit,
+    alpha_g1: [u8; 64],
+    beta_g2: [u8; 128],
+    gamma_g2: [u8; 128],
+    delta_g2: [u8; 128],
+    ic: Vec<[u8; 64]>,
+) -> Result<()> {
+    require!(
+        ic.len() == circuit.public_inputs() + 1,
+        PokerError::InvalidVerifyingKey
+    );
+    
+    let registry = &mut ctx.accounts.vk_registry;
+    let version = registry.current_versions[circuit.index()] + 1;
+    registry.current_versions[circuit.index()] = version;
+    
+    let key = &mut ctx.accounts.verifying_key;
+    key.circuit = circuit;
+    key.version = version;
+    key.alpha_g1 = alpha_g1;
+    key.beta_g2 = beta_g2;
+    key.gamma_g2 = gamma_g2;
+    key.delta_g2 = delta_g2;
+    key.ic = ic;
+    key.bump = *ctx.bumps.get("verifying_key").unwrap();
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(circuit: ProofCircuit)]
+pub struct PublishVerifyingKey<'info> {
+    #[account(
+        mut,
+        seeds = [b"vk_registry"],
+        bump = vk_registry.bump,
+        has_one = admin @ PokerError::NotRegistryAdmin
+    )]
+    pub vk_registry: Account<'info, VerifyingKeyRegistry>,
+    
+    #[account(
+        init,
+        payer = admin,
+        space = VerifyingKeyAccount::LEN,
+        seeds = [
+            b"verifying_key".as_ref(),
+            &[circuit as u8],
+            &(vk_registry.current_versions[circuit.index()] + 1).to_le_bytes()
+        ],
+        bump
+    )]
+    pub verifying_key: Account<'info, VerifyingKeyAccount>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Hand the registry over to a new admin
+pub fn set_vk_admin(ctx: Context<SetVkAdmin>, new_admin: Pubkey) -> Result<()> {
+    ctx.accounts.vk_registry.admin = new_admin;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetVkAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"vk_registry"],
+        bump = vk_registry.bump,
+        has_one = admin @ PokerError::NotRegistryAdmin
+    )]
+    pub vk_registry: Account<'info, VerifyingKeyRegistry>,
+    
+    pub admin: Signer<'info>,
+}
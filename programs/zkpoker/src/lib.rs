@@ -1,11 +1,23 @@
+#![allow(clippy::result_large_err)]
+
 use anchor_lang::prelude::*;
 
 declare_id!("9q1Yd4NeA172qXwvV5GEwqWrN3dHt1yQ3sNtUB2zavqa");
 
-mod state;
-mod instructions;
-mod errors;
-mod poker;
+pub mod state;
+pub mod instructions;
+pub mod errors;
+pub mod engine;
+pub mod poker;
+pub mod elgamal;
+pub mod merkle;
+pub mod modexp;
+pub mod paillier;
+pub mod poseidon;
+pub mod zk;
+
+#[cfg(test)]
+pub(crate) mod test_utils;
 
 use state::*;
 use instructions::*;
@@ -34,26 +46,80 @@ pub mod zkpoker {
         instructions::withdraw_funds(ctx, amount)
     }
 
-    /// Create a new game with player 1's ephemeral public key and Merkle root
+    /// Create a new game with player 1's card key, which selects Paillier or ElGamal
     pub fn create_game(
         ctx: Context<CreateGame>,
         stake_amount: u64,
-        player1_ephemeral_pubkey: EphemeralPubkey,
-        deck_merkle_root: [u8; 32],
+        card_pk: CardPublicKey,
         game_id: u64,
+        invited_opponent: Option<Pubkey>,
+        merkle_hash: MerkleHash,
+        shuffle_verification: ShuffleVerification,
+    ) -> Result<()> {
+        instructions::create_game(
+            ctx,
+            stake_amount,
+            card_pk,
+            game_id,
+            invited_opponent,
+            merkle_hash,
+            shuffle_verification,
+        )
+    }
+
+    /// Player 2 joins the game with their card key for the match's backend
+    pub fn join_game(ctx: Context<JoinGame>, card_pk: CardPublicKey) -> Result<()> {
+        instructions::join_game(ctx, card_pk)
+    }
+
+    /// Start a new hand: rotates the dealer and resets the hand state
+    pub fn start_new_hand(ctx: Context<StartNewHand>) -> Result<()> {
+        instructions::start_new_hand(ctx)
+    }
+
+    /// Non-dealer commits to their encrypted deck and posts blinds
+    pub fn create_hand(
+        ctx: Context<CreateHand>,
+        deck_merkle_root: [u8; 32],
+        deck_creation_proof: ZkProof,
     ) -> Result<()> {
-        instructions::create_game(ctx, stake_amount, player1_ephemeral_pubkey, deck_merkle_root, game_id)
+        instructions::create_hand(ctx, deck_merkle_root, deck_creation_proof)
     }
 
-    /// Player 2 joins the game with their ephemeral public key, doubly-encrypted cards, and Merkle proofs
-    pub fn join_game(
-        ctx: Context<JoinGame>,
-        player2_ephemeral_pubkey: EphemeralPubkey,
-        encrypted_cards: [EncryptedCard; 9],
-        singly_encrypted_cards: [EncryptedCard; 9],
-        merkle_proofs: Vec<MerkleProof>,
+    /// Dealer commits to the reshuffled, doubly-encrypted deck
+    pub fn join_hand(
+        ctx: Context<JoinHand>,
+        doubly_encrypted_deck_merkle_root: [u8; 32],
+        reshuffle_proof: ZkProof,
     ) -> Result<()> {
-        instructions::join_game(ctx, player2_ephemeral_pubkey, encrypted_cards, singly_encrypted_cards, merkle_proofs)
+        instructions::join_hand(ctx, doubly_encrypted_deck_merkle_root, reshuffle_proof)
+    }
+
+    /// Create the buffer that holds the current hand's encrypted decks on-chain
+    pub fn init_deck_buffer(ctx: Context<InitDeckBuffer>) -> Result<()> {
+        instructions::init_deck_buffer(ctx)
+    }
+
+    /// Upload encrypted cards of a committed deck, each checked against its Merkle root
+    pub fn append_deck_chunk(
+        ctx: Context<AppendDeckChunk>,
+        deck: DeckKind,
+        cards: Vec<CommittedCard>,
+    ) -> Result<()> {
+        instructions::append_deck_chunk(ctx, deck, cards)
+    }
+
+    /// Claim the hand if the opponent never finished uploading their committed deck
+    pub fn claim_data_availability_timeout(
+        ctx: Context<ClaimDataAvailabilityTimeout>,
+        deck: DeckKind,
+    ) -> Result<()> {
+        instructions::claim_data_availability_timeout(ctx, deck)
+    }
+
+    /// Close a finished hand's deck buffer and refund its rent
+    pub fn close_deck_buffer(ctx: Context<CloseDeckBuffer>) -> Result<()> {
+        instructions::close_deck_buffer(ctx)
     }
 
     /// Reveal community cards (two-step process for flop, turn, river)
@@ -61,8 +127,18 @@ pub mod zkpoker {
         ctx: Context<RevealCommunityCards>,
         decryption_shares: Vec<EncryptedCard>,
         plaintext_cards: Option<Vec<u8>>,
+        revealed_cards: CardMultiproof,
+        share_proofs: Vec<DecryptionShareProof>,
+        decryption_proofs: Vec<ZkProof>,
     ) -> Result<()> {
-        instructions::reveal_community_cards(ctx, decryption_shares, plaintext_cards)
+        instructions::reveal_community_cards(
+            ctx,
+            decryption_shares,
+            plaintext_cards,
+            revealed_cards,
+            share_proofs,
+            decryption_proofs,
+        )
     }
 
     /// Player action: fold, check, call, or raise
@@ -79,18 +155,106 @@ pub mod zkpoker {
         instructions::advance_street(ctx)
     }
 
-    /// Resolve hand at showdown (two-step process with card verification)
-    pub fn resolve_hand(ctx: Context<ResolveGame>) -> Result<()> {
-        instructions::resolve_hand(ctx)
+    /// Resolve hand at showdown (two-step process, each player reveals their pocket cards)
+    pub fn resolve_hand(
+        ctx: Context<ResolveGame>,
+        pocket_cards: [u8; 2],
+        revealed_cards: CardMultiproof,
+        showdown_proof: Option<ZkProof>,
+        pocket_shares: Vec<PocketCardShares>,
+    ) -> Result<()> {
+        instructions::resolve_hand(
+            ctx,
+            pocket_cards,
+            revealed_cards,
+            showdown_proof,
+            pocket_shares,
+        )
     }
 
     /// Resolve the game and distribute winnings (alias for resolve_hand)
-    pub fn resolve_game(ctx: Context<ResolveGame>) -> Result<()> {
-        instructions::resolve_game(ctx)
+    pub fn resolve_game(
+        ctx: Context<ResolveGame>,
+        pocket_cards: [u8; 2],
+        revealed_cards: CardMultiproof,
+        showdown_proof: Option<ZkProof>,
+        pocket_shares: Vec<PocketCardShares>,
+    ) -> Result<()> {
+        instructions::resolve_game(
+            ctx,
+            pocket_cards,
+            revealed_cards,
+            showdown_proof,
+            pocket_shares,
+        )
+    }
+
+    /// Leave the game between hands, settling both stacks back to player balances
+    pub fn leave_game(ctx: Context<LeaveGame>) -> Result<()> {
+        instructions::leave_game(ctx)
+    }
+
+    /// Open the on-chain check of a disputed card decryption by revealing the Paillier secret
+    pub fn open_verification_job(
+        ctx: Context<OpenVerificationJob>,
+        lambda: Vec<u8>,
+        lambda_inverse: Vec<u8>,
+        montgomery_r2: Vec<u8>,
+    ) -> Result<()> {
+        instructions::open_verification_job(ctx, lambda, lambda_inverse, montgomery_r2)
+    }
+
+    /// Advance a verification job; settles the dispute once it completes
+    pub fn crank_verification(ctx: Context<CrankVerification>) -> Result<()> {
+        instructions::crank_verification(ctx)
+    }
+
+    /// Open a bisection game over a disputed card decryption (accused player)
+    pub fn open_bisection(
+        ctx: Context<OpenBisection>,
+        lambda: Vec<u8>,
+        lambda_inverse: Vec<u8>,
+        montgomery_r2: Vec<u8>,
+    ) -> Result<()> {
+        instructions::open_bisection(ctx, lambda, lambda_inverse, montgomery_r2)
+    }
+
+    /// Post the claimed accumulator at the current bisection midpoint (accused player)
+    pub fn bisection_post_midpoint(ctx: Context<BisectionMove>, value: Vec<u8>) -> Result<()> {
+        instructions::bisection_post_midpoint(ctx, value)
+    }
+
+    /// Accept or reject the posted midpoint; settles the dispute after the last round
+    pub fn bisection_choose(ctx: Context<BisectionMove>, agree_with_midpoint: bool) -> Result<()> {
+        instructions::bisection_choose(ctx, agree_with_midpoint)
+    }
+
+    /// Claim timeout win if opponent doesn't act within time limit,
+    /// or dispute one of the opponent's stored proofs
+    pub fn claim_timeout(ctx: Context<ClaimTimeout>, disputed_action: DisputedAction) -> Result<()> {
+        instructions::claim_timeout(ctx, disputed_action)
+    }
+
+    /// Create the verifying key registry (program upgrade authority only)
+    pub fn initialize_vk_registry(ctx: Context<InitializeVkRegistry>) -> Result<()> {
+        instructions::initialize_vk_registry(ctx)
+    }
+
+    /// Publish the next version of a circuit's verifying key (registry admin only)
+    pub fn publish_verifying_key(
+        ctx: Context<PublishVerifyingKey>,
+        circuit: ProofCircuit,
+        alpha_g1: [u8; 64],
+        beta_g2: [u8; 128],
+        gamma_g2: [u8; 128],
+        delta_g2: [u8; 128],
+        ic: Vec<[u8; 64]>,
+    ) -> Result<()> {
+        instructions::publish_verifying_key(ctx, circuit, alpha_g1, beta_g2, gamma_g2, delta_g2, ic)
     }
 
-    /// Claim timeout win if opponent doesn't act within time limit
-    pub fn claim_timeout(ctx: Context<ClaimTimeout>) -> Result<()> {
-        instructions::claim_timeout(ctx)
+    /// Hand the verifying key registry over to a new admin
+    pub fn set_vk_admin(ctx: Context<SetVkAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::set_vk_admin(ctx, new_admin)
     }
 }
@@ -0,0 +1,637 @@
+use anchor_lang::solana_program::{hash, keccak};
+use crate::poseidon;
+use crate::state::MerkleHash;
+
+// Merkle tree over encrypted deck cards, with the hash chosen per game
+//
+// Matches `PaillierMentalPoker.buildMerkleTree` in the client:
+// - Leaves are H(0x00 || deck_index || H(ciphertext bytes))
+// - Parents are H(0x01 || left || right)
+// - An odd node at the end of a level is promoted to the next level unchanged
+//
+// The prefixes keep leaves and inner nodes apart, and hashing the deck index binds each
+// ciphertext to its position so a card can't be revealed from a different slot. Leaves
+// commit to the ciphertext digest, so reveals can carry 32 bytes per card instead of the
+// full ciphertext.
+//
+// Poseidon hashes field elements rather than bytes, so it uses the same layout with the
+// prefix moved into the capacity element: leaves are Poseidon_1(deck_index, digest) and
+// parents are plain Poseidon(left, right). Ciphertexts are absorbed 31 bytes at a time,
+// starting from their length. Digests and siblings must be canonical field elements, so
+// Poseidon leaves and nodes are None for 32-byte values at or above the field order.
+
+/// Domain separation prefix for leaves
+pub const LEAF_PREFIX: u8 = 0x00;
+
+/// Domain separation prefix for inner nodes
+pub const NODE_PREFIX: u8 = 0x01;
+
+/// Poseidon capacity element for leaves (inner nodes use 0)
+pub const POSEIDON_LEAF_DOMAIN: u64 = 1;
+
+impl MerkleHash {
+    /// Digest of arbitrary bytes
+    pub fn digest(self, data: &[u8]) -> [u8; 32] {
+        match self {
+            MerkleHash::Keccak256 => keccak::hash(data).to_bytes(),
+            MerkleHash::Sha256 => hash::hash(data).to_bytes(),
+            MerkleHash::Poseidon => poseidon::hash_bytes(data),
+        }
+    }
+    
+    /// Leaf for a card digest at its deck position
+    pub fn leaf(self, deck_index: u8, digest: &[u8; 32]) -> Option<[u8; 32]> {
+        match self {
+            MerkleHash::Keccak256 => Some(keccak::hashv(&[&[LEAF_PREFIX, deck_index], digest]).to_bytes()),
+            MerkleHash::Sha256 => Some(hash::hashv(&[&[LEAF_PREFIX, deck_index], digest]).to_bytes()),
+            MerkleHash::Poseidon => {
+                let mut index = [0u8; 32];
+                index[31] = deck_index;
+                poseidon::hash_with_domain(POSEIDON_LEAF_DOMAIN, &index, digest)
+            }
+        }
+    }
+    
+    /// Parent of two sibling nodes
+    pub fn node(self, left: &[u8; 32], right: &[u8; 32]) -> Option<[u8; 32]> {
+        match self {
+            MerkleHash::Keccak256 => Some(keccak::hashv(&[&[NODE_PREFIX], left, right]).to_bytes()),
+            MerkleHash::Sha256 => Some(hash::hashv(&[&[NODE_PREFIX], left, right]).to_bytes()),
+            MerkleHash::Poseidon => poseidon::hash(left, right),
+        }
+    }
+}
+
+/// Digest of an encrypted card, as committed to by its leaf
+pub fn ciphertext_digest(hash: MerkleHash, ciphertext: &[u8]) -> [u8; 32] {
+    hash.digest(ciphertext)
+}
+
+/// Hash a leaf (an encrypted card at its deck position)
+pub fn hash_leaf(hash: MerkleHash, deck_index: u8, ciphertext: &[u8]) -> Option<[u8; 32]> {
+    hash.leaf(deck_index, &ciphertext_digest(hash, ciphertext))
+}
+
+/// Hash a leaf from the digest of its encrypted card
+pub fn hash_leaf_digest(hash: MerkleHash, deck_index: u8, digest: &[u8; 32]) -> Option<[u8; 32]> {
+    hash.leaf(deck_index, digest)
+}
+
+/// Hash two sibling nodes
+pub fn hash_pair(hash: MerkleHash, left: &[u8; 32], right: &[u8; 32]) -> Option<[u8; 32]> {
+    hash.node(left, right)
+}
+
+/// Hash one level of the tree into the next, promoting an odd last node
+fn next_level(hash: MerkleHash, level: &[[u8; 32]]) -> Option<Vec<[u8; 32]>> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => hash.node(left, right),
+            [single] => Some(*single),
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Compute the root of a tree from its leaf hashes
+pub fn compute_root(hash: MerkleHash, leaves: &[[u8; 32]]) -> Option<[u8; 32]> {
+    if leaves.is_empty() {
+        return None;
+    }
+    
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(hash, &level)?;
+    }
+    Some(level[0])
+}
+
+/// Build the inclusion proof (sibling hashes, bottom-up) for the leaf at `index`
+/// Promoted nodes have no sibling, so proofs can be shorter than the tree height
+pub fn build_proof(hash: MerkleHash, leaves: &[[u8; 32]], index: usize) -> Option<Vec<[u8; 32]>> {
+    if index >= leaves.len() {
+        return None;
+    }
+    
+    let mut proof = Vec::new();
+    let mut level = leaves.to_vec();
+    let mut idx = index;
+    while level.len() > 1 {
+        if idx % 2 == 1 {
+            proof.push(level[idx - 1]);
+        } else if idx + 1 < level.len() {
+            proof.push(level[idx + 1]);
+        }
+        level = next_level(hash, &level)?;
+        idx /= 2;
+    }
+    Some(proof)
+}
+
+/// Recompute the root from a leaf hash and its inclusion proof
+/// Returns None if the proof has the wrong length for this position
+pub fn root_from_proof(
+    hash: MerkleHash,
+    leaf: [u8; 32],
+    index: usize,
+    leaf_count: usize,
+    proof: &[[u8; 32]],
+) -> Option<[u8; 32]> {
+    if index >= leaf_count {
+        return None;
+    }
+    
+    let mut node = leaf;
+    let mut idx = index;
+    let mut width = leaf_count;
+    let mut siblings = proof.iter();
+    while width > 1 {
+        if idx % 2 == 1 {
+            node = hash.node(siblings.next()?, &node)?;
+        } else if idx + 1 < width {
+            node = hash.node(&node, siblings.next()?)?;
+        }
+        idx /= 2;
+        width = width.div_ceil(2);
+    }
+    
+    if siblings.next().is_some() {
+        return None;
+    }
+    Some(node)
+}
+
+/// Check that `ciphertext` is the card at `deck_index` of the tree committed to by `root`
+pub fn verify_inclusion(
+    hash: MerkleHash,
+    root: &[u8; 32],
+    deck_index: u8,
+    ciphertext: &[u8],
+    leaf_count: usize,
+    proof: &[[u8; 32]],
+) -> bool {
+    hash_leaf(hash, deck_index, ciphertext)
+        .and_then(|leaf| root_from_proof(hash, leaf, deck_index as usize, leaf_count, proof))
+        .as_ref()
+        == Some(root)
+}
+
+/// Build a multiproof for several leaves at once
+/// `indices` must be strictly increasing. Siblings are listed level by level, left to
+/// right, and only where they can't be computed from the revealed leaves themselves, so
+/// paths shared between the leaves are sent once.
+pub fn build_multiproof(hash: MerkleHash, leaves: &[[u8; 32]], indices: &[usize]) -> Option<Vec<[u8; 32]>> {
+    if indices.is_empty()
+        || indices.windows(2).any(|pair| pair[0] >= pair[1])
+        || indices[indices.len() - 1] >= leaves.len()
+    {
+        return None;
+    }
+    
+    let mut proof = Vec::new();
+    let mut level = leaves.to_vec();
+    let mut known = indices.to_vec();
+    while level.len() > 1 {
+        let mut parents = Vec::with_capacity(known.len());
+        let mut i = 0;
+        while i < known.len() {
+            let idx = known[i];
+            let sibling = idx ^ 1;
+            if sibling < level.len() {
+                if known.get(i + 1) == Some(&sibling) {
+                    i += 1;
+                } else {
+                    proof.push(level[sibling]);
+                }
+            }
+            parents.push(idx / 2);
+            i += 1;
+        }
+        level = next_level(hash, &level)?;
+        known = parents;
+    }
+    Some(proof)
+}
+
+/// Recompute the root from several (index, leaf hash) pairs and their multiproof
+/// Returns the root and the number of node hashes it took, or None if the proof
+/// doesn't fit these positions.
+fn root_from_multiproof_counted(
+    hash: MerkleHash,
+    leaves: &[(usize, [u8; 32])],
+    leaf_count: usize,
+    proof: &[[u8; 32]],
+) -> Option<([u8; 32], usize)> {
+    if leaves.is_empty()
+        || leaves.windows(2).any(|pair| pair[0].0 >= pair[1].0)
+        || leaves[leaves.len() - 1].0 >= leaf_count
+    {
+        return None;
+    }
+    
+    let mut nodes = leaves.to_vec();
+    let mut width = leaf_count;
+    let mut siblings = proof.iter();
+    let mut hashes = 0;
+    while width > 1 {
+        let mut parents = Vec::with_capacity(nodes.len());
+        let mut i = 0;
+        while i < nodes.len() {
+            let (idx, node) = nodes[i];
+            let parent = if idx % 2 == 1 {
+                hash.node(siblings.next()?, &node)?
+            } else if idx + 1 >= width {
+                // Promoted without hashing
+                parents.push((idx / 2, node));
+                i += 1;
+                continue;
+            } else if nodes.get(i + 1).is_some_and(|(next, _)| *next == idx + 1) {
+                i += 1;
+                hash.node(&node, &nodes[i].1)?
+            } else {
+                hash.node(&node, siblings.next()?)?
+            };
+            hashes += 1;
+            parents.push((idx / 2, parent));
+            i += 1;
+        }
+        nodes = parents;
+        width = width.div_ceil(2);
+    }
+    
+    if siblings.next().is_some() {
+        return None;
+    }
+    Some((nodes[0].1, hashes))
+}
+
+/// Recompute the root from several (index, leaf hash) pairs and their multiproof
+pub fn root_from_multiproof(
+    hash: MerkleHash,
+    leaves: &[(usize, [u8; 32])],
+    leaf_count: usize,
+    proof: &[[u8; 32]],
+) -> Option<[u8; 32]> {
+    root_from_multiproof_counted(hash, leaves, leaf_count, proof).map(|(root, _)| root)
+}
+
+/// Check that several (index, leaf hash) pairs all belong to the tree committed to by `root`
+pub fn verify_multiproof(
+    hash: MerkleHash,
+    root: &[u8; 32],
+    leaves: &[(usize, [u8; 32])],
+    leaf_count: usize,
+    proof: &[[u8; 32]],
+) -> bool {
+    root_from_multiproof(hash, leaves, leaf_count, proof).as_ref() == Some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto_bigint::{Encoding, U256};
+    use crate::state::{
+        CardMultiproof, DecryptionShareProof, EncryptedCard, RevealedCard, DEALER_POCKET_INDICES,
+        DECK_SIZE, FLOP_CARD_INDICES, PocketCardShares, ZkProof,
+    };
+    
+    const KECCAK: MerkleHash = MerkleHash::Keccak256;
+    
+    fn deck_leaves() -> Vec<[u8; 32]> {
+        (0..DECK_SIZE).map(|i| hash_leaf(KECCAK, i, &[i; 64]).unwrap()).collect()
+    }
+    
+    #[test]
+    fn test_every_deck_position_verifies() {
+        let leaves = deck_leaves();
+        let root = compute_root(KECCAK, &leaves).unwrap();
+        
+        for index in 0..DECK_SIZE {
+            let proof = build_proof(KECCAK, &leaves, index as usize).unwrap();
+            assert!(verify_inclusion(KECCAK, &root, index, &[index; 64], leaves.len(), &proof));
+        }
+    }
+    
+    #[test]
+    fn test_tampered_inclusion_rejected() {
+        let leaves = deck_leaves();
+        let root = compute_root(KECCAK, &leaves).unwrap();
+        let proof = build_proof(KECCAK, &leaves, 50).unwrap();
+        
+        // Wrong data, wrong position, truncated and extended proofs
+        assert!(!verify_inclusion(KECCAK, &root, 50, &[51u8; 64], leaves.len(), &proof));
+        assert!(!verify_inclusion(KECCAK, &root, 51, &[50u8; 64], leaves.len(), &proof));
+        assert!(!verify_inclusion(KECCAK, &root, 50, &[50u8; 64], leaves.len(), &proof[1..]));
+        let mut extended = proof.clone();
+        extended.push([0u8; 32]);
+        assert!(!verify_inclusion(KECCAK, &root, 50, &[50u8; 64], leaves.len(), &extended));
+    }
+    
+    #[test]
+    fn test_odd_levels_promote_last_node() {
+        // Three leaves: root = H(H(a, b), c)
+        let leaves = [
+            hash_leaf(KECCAK, 0, b"a").unwrap(),
+            hash_leaf(KECCAK, 1, b"b").unwrap(),
+            hash_leaf(KECCAK, 2, b"c").unwrap(),
+        ];
+        let expected = hash_pair(KECCAK, &hash_pair(KECCAK, &leaves[0], &leaves[1]).unwrap(), &leaves[2]).unwrap();
+        assert_eq!(compute_root(KECCAK, &leaves), Some(expected));
+        assert_eq!(build_proof(KECCAK, &leaves, 2).unwrap().len(), 1);
+    }
+    
+    #[test]
+    fn test_multiproof_matches_single_proofs() {
+        let leaves = deck_leaves();
+        let root = compute_root(KECCAK, &leaves).unwrap();
+        
+        // Flop, all-in runout, both pocket card pairs, scattered positions, whole deck
+        let sets: [Vec<usize>; 6] = [
+            vec![4, 5, 6],
+            (4..=8).collect(),
+            vec![0, 2],
+            vec![1, 3],
+            vec![0, 17, 31, 50, 51],
+            (0..DECK_SIZE as usize).collect(),
+        ];
+        for indices in sets.iter() {
+            let proof = build_multiproof(KECCAK, &leaves, indices).unwrap();
+            let revealed: Vec<(usize, [u8; 32])> = indices.iter().map(|&i| (i, leaves[i])).collect();
+            assert!(verify_multiproof(KECCAK, &root, &revealed, leaves.len(), &proof));
+            
+            // Never larger than the independent proofs combined
+            let independent: usize = indices.iter().map(|&i| build_proof(KECCAK, &leaves, i).unwrap().len()).sum();
+            assert!(proof.len() <= independent);
+        }
+        assert!(build_multiproof(KECCAK, &leaves, &(0..DECK_SIZE as usize).collect::<Vec<_>>()).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_tampered_multiproof_rejected() {
+        let leaves = deck_leaves();
+        let root = compute_root(KECCAK, &leaves).unwrap();
+        let proof = build_multiproof(KECCAK, &leaves, &[4, 5, 6]).unwrap();
+        let flop = [(4, leaves[4]), (5, leaves[5]), (6, leaves[6])];
+        
+        // Substituted leaf, shifted positions, unsorted leaves, truncated and extended proofs
+        let mut substituted = flop;
+        substituted[1].1 = leaves[9];
+        assert!(!verify_multiproof(KECCAK, &root, &substituted, leaves.len(), &proof));
+        let shifted = [(5, leaves[4]), (6, leaves[5]), (7, leaves[6])];
+        assert!(!verify_multiproof(KECCAK, &root, &shifted, leaves.len(), &proof));
+        assert!(!verify_multiproof(KECCAK, &root, &[flop[1], flop[0], flop[2]], leaves.len(), &proof));
+        assert!(!verify_multiproof(KECCAK, &root, &flop, leaves.len(), &proof[1..]));
+        let mut extended = proof.clone();
+        extended.push([0u8; 32]);
+        assert!(!verify_multiproof(KECCAK, &root, &flop, leaves.len(), &extended));
+        assert!(!verify_multiproof(KECCAK, &root, &[], leaves.len(), &[]));
+    }
+    
+    #[test]
+    fn test_multiproof_uses_fewer_hashes() {
+        let leaves = deck_leaves();
+        for indices in [vec![4, 5, 6], (4..=8).collect(), vec![0, 2], vec![1, 3]] {
+            let proof = build_multiproof(KECCAK, &leaves, &indices).unwrap();
+            let revealed: Vec<(usize, [u8; 32])> = indices.iter().map(|&i| (i, leaves[i])).collect();
+            let (_, hashes) = root_from_multiproof_counted(KECCAK, &revealed, leaves.len(), &proof).unwrap();
+            
+            // Each independent proof hashes once per sibling on its path
+            let independent: usize = indices.iter().map(|&i| build_proof(KECCAK, &leaves, i).unwrap().len()).sum();
+            assert!(hashes < independent, "{:?}: {} vs {}", indices, hashes, independent);
+        }
+    }
+    
+    /// Maximum serialized transaction size (`PACKET_DATA_SIZE`)
+    const PACKET_DATA_SIZE: usize = 1232;
+    
+    fn compact_len(value: usize) -> usize {
+        match value {
+            0..=0x7f => 1,
+            0x80..=0x3fff => 2,
+            _ => 3,
+        }
+    }
+    
+    /// Size of a v0 transaction with one signer, one instruction and no lookup tables
+    fn v0_transaction_size(instruction_accounts: usize, data_len: usize) -> usize {
+        let keys = instruction_accounts + 1; // plus the program id
+        let signatures = compact_len(1) + 64;
+        let message = 1 // version prefix
+            + 3 // header
+            + compact_len(keys) + 32 * keys
+            + 32 // recent blockhash
+            + compact_len(1)
+            + 1 + compact_len(instruction_accounts) + instruction_accounts
+            + compact_len(data_len) + data_len
+            + compact_len(0); // address table lookups
+        signatures + message
+    }
+    
+    fn card_multiproof(leaves: &[[u8; 32]], card_indices: &[u8]) -> CardMultiproof {
+        let positions: Vec<usize> = card_indices.iter().map(|&i| i as usize).collect();
+        CardMultiproof {
+            cards: card_indices
+                .iter()
+                .map(|&i| RevealedCard { card_index: i, ciphertext_digest: ciphertext_digest(KECCAK, &[i; 64]) })
+                .collect(),
+            proof: build_multiproof(KECCAK, leaves, &positions).unwrap(),
+        }
+    }
+    
+    #[test]
+    fn test_reveal_transactions_fit() {
+        use anchor_lang::InstructionData;
+        
+        let leaves = deck_leaves();
+        let flop = card_multiproof(&leaves, &FLOP_CARD_INDICES);
+        
+        // Flop reveal completion: plaintext cards plus the multiproof (game, player)
+        let reveal = crate::instruction::RevealCommunityCards {
+            decryption_shares: vec![],
+            plaintext_cards: Some(vec![12, 25, 38]),
+            revealed_cards: flop.clone(),
+            share_proofs: vec![],
+            decryption_proofs: vec![],
+        };
+        let flop_size = v0_transaction_size(2, reveal.data().len());
+        assert!(flop_size <= PACKET_DATA_SIZE, "flop reveal is {} bytes", flop_size);
+        
+        // The same reveal with three independent 6-level paths
+        let independent: usize = FLOP_CARD_INDICES
+            .iter()
+            .map(|&i| build_proof(KECCAK, &leaves, i as usize).unwrap().len() * 32)
+            .sum();
+        assert!(flop.proof.len() * 32 < independent);
+        
+        // All-in runout of the whole board
+        let runout = crate::instruction::RevealCommunityCards {
+            decryption_shares: vec![],
+            plaintext_cards: Some(vec![1, 2, 3, 4, 5]),
+            revealed_cards: card_multiproof(&leaves, &[4, 5, 6, 7, 8]),
+            share_proofs: vec![],
+            decryption_proofs: vec![],
+        };
+        assert!(v0_transaction_size(2, runout.data().len()) <= PACKET_DATA_SIZE);
+        
+        // ElGamal flop: 32-byte shares, each with its 64-byte ciphertext and proof
+        let elgamal_flop = crate::instruction::RevealCommunityCards {
+            decryption_shares: vec![EncryptedCard { data: vec![0; 32] }; 3],
+            plaintext_cards: None,
+            revealed_cards: flop.clone(),
+            share_proofs: vec![
+                DecryptionShareProof { ciphertext: vec![0; 64], challenge: [0; 32], response: [0; 32] };
+                3
+            ],
+            decryption_proofs: vec![],
+        };
+        let elgamal_size = v0_transaction_size(2, elgamal_flop.data().len());
+        assert!(elgamal_size <= PACKET_DATA_SIZE, "ElGamal flop reveal is {} bytes", elgamal_size);
+        
+        // Paillier showdown: both pocket cards with a 256-byte Groth16 proof
+        // (game, both player accounts, verifying key, player)
+        let showdown = crate::instruction::ResolveHand {
+            pocket_cards: [7, 45],
+            revealed_cards: card_multiproof(&leaves, &DEALER_POCKET_INDICES),
+            showdown_proof: Some(ZkProof { proof_data: vec![0; 256] }),
+            pocket_shares: vec![],
+        };
+        assert!(v0_transaction_size(5, showdown.data().len()) <= PACKET_DATA_SIZE);
+        
+        // ElGamal showdown: both players' proven shares of both pocket cards
+        let elgamal_showdown = crate::instruction::ResolveHand {
+            pocket_cards: [7, 45],
+            revealed_cards: card_multiproof(&leaves, &DEALER_POCKET_INDICES),
+            showdown_proof: None,
+            pocket_shares: vec![
+                PocketCardShares {
+                    ciphertext: vec![0; 64],
+                    shares: [[0; 32]; 2],
+                    challenges: [[0; 32]; 2],
+                    responses: [[0; 32]; 2],
+                };
+                2
+            ],
+        };
+        let elgamal_size = v0_transaction_size(5, elgamal_showdown.data().len());
+        assert!(elgamal_size <= PACKET_DATA_SIZE, "ElGamal showdown is {} bytes", elgamal_size);
+    }
+    
+    #[test]
+    fn test_leaf_encoding_is_domain_separated() {
+        for hash in [MerkleHash::Keccak256, MerkleHash::Sha256, MerkleHash::Poseidon] {
+            // The same ciphertext at another position is a different leaf
+            assert_ne!(hash_leaf(hash, 4, b"card"), hash_leaf(hash, 5, b"card"));
+            
+            // A leaf is not the node of its position and digest
+            let digest = hash.digest(b"card");
+            let mut index = [0u8; 32];
+            index[31] = 7;
+            assert_ne!(hash.leaf(7, &digest), hash.node(&index, &digest));
+        }
+    }
+    
+    fn hex(value: &str) -> [u8; 32] {
+        U256::from_be_hex(value).to_be_bytes()
+    }
+    
+    #[test]
+    fn test_hashes_match_known_vectors() {
+        assert_eq!(
+            MerkleHash::Keccak256.digest(b""),
+            hex("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+        assert_eq!(
+            MerkleHash::Sha256.digest(b"abc"),
+            hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        
+        // Poseidon inner nodes are circomlib Poseidon(left, right)
+        assert_eq!(
+            MerkleHash::Poseidon.node(
+                &U256::from_u64(1).to_be_bytes(),
+                &U256::from_u64(2).to_be_bytes()
+            ),
+            Some(hex("115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a"))
+        );
+    }
+    
+    #[test]
+    fn test_leaf_and_root_vectors() {
+        // From an independent Python implementation (Keccak-f[1600] checked against
+        // hashlib's SHA3-256, Poseidon with constants regenerated from the Grain LFSR
+        // and checked against circomlibjs): the leaf of [7; 64] at position 7 and the
+        // root of the deck with [i; 64] at position i
+        let vectors = [
+            (
+                MerkleHash::Keccak256,
+                "a1c5dc2ccfb96769dae67bee9582a5c18c15e879dd27332f97d9ea60e2ed036e",
+                "2ceea16a2aaae5d7dbcfa09e45fdfb35d51511f702c07ea5e45cdf15d1a4a90e",
+            ),
+            (
+                MerkleHash::Sha256,
+                "f3b168aa55bfec964a873202420859f4c2fcd209de3c3bc72fe38b72be40bfed",
+                "1ea7334df45c09649b59661d53e267a1f73c7de47e7c4355de34102c73df8cae",
+            ),
+            (
+                MerkleHash::Poseidon,
+                "0f4423d602fc83666b5e5be9bd2f75349c334aaaaf395e0c9acd6c05aff39f7b",
+                "29dfab4009f72ba487596df41f8343c32b28c3c889dd73b6c9f4c9abe775867b",
+            ),
+        ];
+        for (hash, leaf, root) in vectors {
+            let leaves: Vec<[u8; 32]> = (0..DECK_SIZE).map(|i| hash_leaf(hash, i, &[i; 64]).unwrap()).collect();
+            assert_eq!(leaves[7], hex(leaf));
+            assert_eq!(compute_root(hash, &leaves), Some(hex(root)));
+        }
+    }
+    
+    #[test]
+    fn test_poseidon_rejects_non_canonical_nodes() {
+        let hash = MerkleHash::Poseidon;
+        let leaves: Vec<[u8; 32]> = (0..DECK_SIZE).map(|i| hash_leaf(hash, i, &[i; 64]).unwrap()).collect();
+        let root = compute_root(hash, &leaves).unwrap();
+        let proof = build_proof(hash, &leaves, 17).unwrap();
+        assert!(verify_inclusion(hash, &root, 17, &[17u8; 64], leaves.len(), &proof));
+        
+        // A sibling plus the field order would reduce to the same node
+        let modulus = U256::from_be_hex("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001");
+        let mut aliased = proof.clone();
+        aliased[0] = U256::from_be_bytes(proof[0]).wrapping_add(&modulus).to_be_bytes();
+        assert!(!verify_inclusion(hash, &root, 17, &[17u8; 64], leaves.len(), &aliased));
+        let flop: Vec<(usize, [u8; 32])> = (4..=6).map(|i| (i, leaves[i])).collect();
+        let mut multiproof = build_multiproof(hash, &leaves, &[4, 5, 6]).unwrap();
+        assert!(verify_multiproof(hash, &root, &flop, leaves.len(), &multiproof));
+        multiproof[0] = U256::from_be_bytes(multiproof[0]).wrapping_add(&modulus).to_be_bytes();
+        assert!(!verify_multiproof(hash, &root, &flop, leaves.len(), &multiproof));
+        
+        // Digests are checked the same way
+        assert_eq!(hash.leaf(7, &modulus.to_be_bytes()), None);
+    }
+    
+    #[test]
+    fn test_poseidon_digests_bind_length() {
+        // Digests bind the length, so trailing zero bytes matter
+        assert_ne!(MerkleHash::Poseidon.digest(&[1, 0]), MerkleHash::Poseidon.digest(&[1]));
+        assert_ne!(MerkleHash::Poseidon.digest(&[]), MerkleHash::Poseidon.digest(&[0]));
+    }
+    
+    #[test]
+    fn test_every_hash_commits_the_deck() {
+        let mut roots = Vec::new();
+        for hash in [MerkleHash::Keccak256, MerkleHash::Sha256, MerkleHash::Poseidon] {
+            let leaves: Vec<[u8; 32]> = (0..DECK_SIZE).map(|i| hash_leaf(hash, i, &[i; 64]).unwrap()).collect();
+            let root = compute_root(hash, &leaves).unwrap();
+            
+            let proof = build_proof(hash, &leaves, 17).unwrap();
+            assert!(verify_inclusion(hash, &root, 17, &[17u8; 64], leaves.len(), &proof));
+            assert!(!verify_inclusion(hash, &root, 17, &[18u8; 64], leaves.len(), &proof));
+            
+            let flop: Vec<(usize, [u8; 32])> = (4..=6).map(|i| (i, leaves[i])).collect();
+            let proof = build_multiproof(hash, &leaves, &[4, 5, 6]).unwrap();
+            assert!(verify_multiproof(hash, &root, &flop, leaves.len(), &proof));
+            roots.push(root);
+        }
+        assert!(roots[0] != roots[1] && roots[1] != roots[2] && roots[0] != roots[2]);
+    }
+}
@@ -0,0 +1,183 @@
+use crypto_bigint::modular::montgomery_reduction;
+use crypto_bigint::subtle::{Choice, ConditionallySelectable};
+use crypto_bigint::{Limb, Word, U4096};
+
+// Montgomery arithmetic modulo n^2 for Paillier verification
+//
+// `DynResidueParams::new` computes R^2 mod n^2 with a full-width long division, which
+// does not fit in a transaction's compute budget. Instead the caller supplies R^2 mod n^2
+// as a hint and it is checked with a single Montgomery reduction: REDC(R^2) must equal
+// R mod n^2, which is cheap to compute because n^2 is close to the width of U4096.
+
+/// Width of values modulo n^2 (2048-bit keys)
+pub type WideUint = U4096;
+
+/// Serialized size of a WideUint
+pub const WIDE_UINT_BYTES: usize = 512;
+
+/// Parse a big-endian integer of at most WIDE_UINT_BYTES bytes
+pub fn wide_from_be_bytes(bytes: &[u8]) -> Option<WideUint> {
+    if bytes.len() > WIDE_UINT_BYTES {
+        return None;
+    }
+    let mut padded = [0u8; WIDE_UINT_BYTES];
+    padded[WIDE_UINT_BYTES - bytes.len()..].copy_from_slice(bytes);
+    Some(WideUint::from_be_slice(&padded))
+}
+
+/// Serialize as a fixed-width big-endian integer
+pub fn wide_to_be_bytes(value: &WideUint) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(WIDE_UINT_BYTES);
+    for limb in value.as_limbs().iter().rev() {
+        bytes.extend_from_slice(&limb.0.to_be_bytes());
+    }
+    bytes
+}
+
+/// Montgomery context for an odd modulus
+pub struct MontgomeryContext {
+    pub modulus: WideUint,
+    mod_neg_inv: Limb,
+    /// R mod modulus, i.e. 1 in Montgomery form
+    r: WideUint,
+}
+
+impl MontgomeryContext {
+    /// Returns None for an even or zero modulus
+    pub fn new(modulus: WideUint) -> Option<Self> {
+        if modulus.as_words()[0] & 1 == 0 {
+            return None;
+        }
+        
+        let modulus_lo = crypto_bigint::Uint::<1>::from_words([modulus.as_words()[0]]);
+        let mod_neg_inv = Limb(
+            Word::MIN.wrapping_sub(modulus_lo.inv_mod2k_vartime(Word::BITS as usize).as_words()[0]),
+        );
+        let r = WideUint::MAX.const_rem(&modulus).0.wrapping_add(&WideUint::ONE);
+        
+        Some(MontgomeryContext { modulus, mod_neg_inv, r })
+    }
+    
+    /// 1 in Montgomery form
+    pub fn one(&self) -> WideUint {
+        self.r
+    }
+    
+    /// Montgomery product a * b * R^-1 mod modulus
+    pub fn mul(&self, a: &WideUint, b: &WideUint) -> WideUint {
+        montgomery_reduction(&a.mul_wide(b), &self.modulus, self.mod_neg_inv)
+    }
+    
+    /// Montgomery square a^2 * R^-1 mod modulus
+    pub fn square(&self, a: &WideUint) -> WideUint {
+        montgomery_reduction(&a.square_wide(), &self.modulus, self.mod_neg_inv)
+    }
+    
+    /// Convert out of Montgomery form
+    pub fn from_montgomery(&self, a: &WideUint) -> WideUint {
+        montgomery_reduction(&(*a, WideUint::ZERO), &self.modulus, self.mod_neg_inv)
+    }
+    
+    /// Check a caller-supplied R^2 mod modulus
+    pub fn is_valid_r2(&self, r2: &WideUint) -> bool {
+        r2 < &self.modulus && self.from_montgomery(r2) == self.r
+    }
+    
+    /// Convert into Montgomery form using a checked R^2 hint
+    pub fn to_montgomery(&self, a: &WideUint, r2: &WideUint) -> WideUint {
+        self.mul(a, r2)
+    }
+    
+    /// Compute R^2 mod modulus by doubling R mod modulus once per bit of R
+    /// Costs one modular addition per bit, so callers short on compute should prefer a hint.
+    pub fn compute_r2(&self) -> WideUint {
+        let mut r2 = self.r;
+        for _ in 0..WideUint::BITS {
+            r2 = r2.add_mod(&r2, &self.modulus);
+        }
+        r2
+    }
+    
+    /// Constant-time base^exponent over the low `bits` bits of the exponent
+    /// The running time depends only on `bits`, never on the exponent's value.
+    pub fn pow(&self, base: &WideUint, exponent: &WideUint, bits: usize) -> WideUint {
+        let mut acc = self.r;
+        for index in (0..bits).rev() {
+            let squared = self.square(&acc);
+            let multiplied = self.mul(&squared, base);
+            acc = WideUint::conditional_select(&squared, &multiplied, Choice::from(exponent.bit(index)));
+        }
+        acc
+    }
+    
+    /// One left-to-right square-and-multiply step for a single exponent bit
+    pub fn pow_step(&self, acc: &WideUint, base: &WideUint, bit: bool) -> WideUint {
+        let squared = self.square(acc);
+        if bit {
+            self.mul(&squared, base)
+        } else {
+            squared
+        }
+    }
+}
+
+/// Read bit `index` (0 = least significant) of a big-endian integer
+pub fn be_bit(bytes: &[u8], index: usize) -> bool {
+    let byte = index / 8;
+    if byte >= bytes.len() {
+        return false;
+    }
+    bytes[bytes.len() - 1 - byte] & (1 << (index % 8)) != 0
+}
+
+/// Number of significant bits of a big-endian integer
+pub fn be_bit_len(bytes: &[u8]) -> usize {
+    match bytes.iter().position(|b| *b != 0) {
+        Some(first) => (bytes.len() - first) * 8 - bytes[first].leading_zeros() as usize,
+        None => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{n_squared, pow, r2_hint, LAMBDA};
+    
+    #[test]
+    fn test_pow_matches_reference() {
+        // Exponents modulo n^2 of the toy key
+        let n2 = n_squared();
+        let ctx = MontgomeryContext::new(n2).unwrap();
+        let r2 = r2_hint(&n2);
+        assert!(ctx.is_valid_r2(&r2));
+        assert!(!ctx.is_valid_r2(&r2.wrapping_add(&WideUint::ONE)));
+        
+        let base = WideUint::from_u64(123456);
+        let exponent = LAMBDA.to_be_bytes();
+        let base_m = ctx.to_montgomery(&base, &r2);
+        let mut acc = ctx.one();
+        for index in (0..be_bit_len(&exponent)).rev() {
+            acc = ctx.pow_step(&acc, &base_m, be_bit(&exponent, index));
+        }
+        
+        let expected = pow(&base, LAMBDA);
+        assert_eq!(ctx.from_montgomery(&acc), expected);
+        
+        // The constant-time ladder agrees, including with extra leading zero bits
+        assert_eq!(ctx.compute_r2(), r2);
+        let ct = ctx.pow(&base_m, &WideUint::from_u64(LAMBDA), 64);
+        assert_eq!(ctx.from_montgomery(&ct), expected);
+    }
+    
+    #[test]
+    fn test_byte_encoding_round_trip() {
+        let value = WideUint::from_u64(0x0102_0304_0506);
+        let bytes = wide_to_be_bytes(&value);
+        assert_eq!(bytes.len(), WIDE_UINT_BYTES);
+        assert_eq!(wide_from_be_bytes(&bytes), Some(value));
+        assert_eq!(wide_from_be_bytes(&[1, 2, 3, 4, 5, 6]), Some(value));
+        assert_eq!(wide_from_be_bytes(&[0u8; WIDE_UINT_BYTES + 1]), None);
+        assert_eq!(be_bit_len(&[0, 0, 0b101]), 3);
+        assert!(be_bit(&[0b100, 0], 10));
+    }
+}
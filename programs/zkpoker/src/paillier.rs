@@ -0,0 +1,443 @@
+use anchor_lang::prelude::*;
+use crypto_bigint::subtle::ConstantTimeEq;
+use crate::errors::PokerError;
+use crate::modexp::{self, MontgomeryContext, WideUint};
+use crate::state::{DecryptionClaim, PaillierPublicKey, MAX_PAILLIER_N_LEN, PAILLIER_N_BITS};
+
+// Paillier encryption primitives shared by the program and off-chain tooling
+//
+// All arithmetic is done in Montgomery form modulo n^2 over U4096, using the constant-time
+// ladder from `modexp`, so the same code runs on SBF and on the host. Values are encoded
+// as big-endian bytes; ciphertexts are padded to the byte length of n^2.
+
+/// A parsed and validated Paillier public key
+pub struct PaillierContext {
+    n: WideUint,
+    /// Significant bits of n, used as the fixed exponent width
+    n_bits: usize,
+    /// Byte length of n^2, the encoded ciphertext width
+    ciphertext_len: usize,
+    /// Generator in Montgomery form
+    g: WideUint,
+    r2: WideUint,
+    mont: MontgomeryContext,
+}
+
+impl PaillierContext {
+    /// Parse and validate a key, computing R^2 mod n^2 on the fly
+    pub fn new(pk: &PaillierPublicKey) -> Result<Self> {
+        Self::build(pk, None)
+    }
+    
+    /// Parse and validate a key with a caller-supplied R^2 mod n^2 hint
+    /// Cheaper than `new` on-chain; the hint is rejected if it does not match the key.
+    pub fn with_r2_hint(pk: &PaillierPublicKey, r2: &[u8]) -> Result<Self> {
+        let r2 = modexp::wide_from_be_bytes(r2).ok_or(PokerError::InvalidMontgomeryHint)?;
+        Self::build(pk, Some(r2))
+    }
+    
+    fn build(pk: &PaillierPublicKey, r2_hint: Option<WideUint>) -> Result<Self> {
+        let (n, g) = parse_key(pk)?;
+        let mont = MontgomeryContext::new(n.wrapping_mul(&n)).ok_or(PokerError::InvalidPaillierKey)?;
+        
+        let r2 = match r2_hint {
+            Some(r2) => {
+                require!(mont.is_valid_r2(&r2), PokerError::InvalidMontgomeryHint);
+                r2
+            }
+            None => mont.compute_r2(),
+        };
+        
+        Ok(PaillierContext {
+            n,
+            n_bits: n.bits_vartime(),
+            ciphertext_len: mont.modulus.bits_vartime().div_ceil(8),
+            g: mont.to_montgomery(&g, &r2),
+            r2,
+            mont,
+        })
+    }
+    
+    /// Byte length of an encoded ciphertext
+    pub fn ciphertext_len(&self) -> usize {
+        self.ciphertext_len
+    }
+    
+    /// Encrypt m with randomness r: c = g^m · r^n mod n^2
+    pub fn encrypt(&self, plaintext: &[u8], randomness: &[u8]) -> Result<Vec<u8>> {
+        let m = self.parse_plaintext(plaintext)?;
+        let r = self.parse_randomness(randomness)?;
+        let c = self.mont.mul(&self.mont.pow(&self.g, &m, self.n_bits), &self.blinding(&r));
+        Ok(self.encode(&c))
+    }
+    
+    /// Homomorphic addition: Enc(m1) · Enc(m2) = Enc(m1 + m2 mod n)
+    pub fn add(&self, lhs: &[u8], rhs: &[u8]) -> Result<Vec<u8>> {
+        let lhs = self.parse_ciphertext(lhs)?;
+        let rhs = self.parse_ciphertext(rhs)?;
+        Ok(self.encode(&self.mont.mul(&lhs, &rhs)))
+    }
+    
+    /// Rerandomize a ciphertext without changing its plaintext: c · r^n mod n^2
+    pub fn rerandomize(&self, ciphertext: &[u8], randomness: &[u8]) -> Result<Vec<u8>> {
+        let c = self.parse_ciphertext(ciphertext)?;
+        let r = self.parse_randomness(randomness)?;
+        Ok(self.encode(&self.mont.mul(&c, &self.blinding(&r))))
+    }
+    
+    /// Check that a ciphertext is the encryption of m under the revealed randomness r
+    pub fn verify_encryption(&self, ciphertext: &[u8], plaintext: &[u8], randomness: &[u8]) -> Result<bool> {
+        let c = self.parse_ciphertext(ciphertext)?;
+        let expected = self.encrypt(plaintext, randomness)?;
+        let expected = self.parse_ciphertext(&expected)?;
+        Ok(bool::from(c.ct_eq(&expected)))
+    }
+    
+    /// Check that a ciphertext decrypts to m using the secret λ
+    /// c^λ = g^(mλ) mod n^2 holds exactly for the correct plaintext, since r^(nλ) = 1
+    /// and g^λ has order n, so no division by n is needed.
+    pub fn verify_decryption(&self, ciphertext: &[u8], plaintext: &[u8], lambda: &[u8]) -> Result<bool> {
+        let c = self.parse_ciphertext(ciphertext)?;
+        let m = self.parse_plaintext(plaintext)?;
+        let lambda = modexp::wide_from_be_bytes(lambda).ok_or(PokerError::InvalidVerificationJob)?;
+        require!(
+            lambda != WideUint::ZERO && lambda < self.n,
+            PokerError::InvalidVerificationJob
+        );
+        
+        let c_lambda = self.mont.pow(&c, &lambda, self.n_bits);
+        let g_lambda = self.mont.pow(&self.g, &lambda, self.n_bits);
+        let expected = self.mont.pow(&g_lambda, &m, self.n_bits);
+        Ok(bool::from(c_lambda.ct_eq(&expected)))
+    }
+    
+    /// r^n in Montgomery form
+    fn blinding(&self, r: &WideUint) -> WideUint {
+        let r = self.mont.to_montgomery(r, &self.r2);
+        self.mont.pow(&r, &self.n, self.n_bits)
+    }
+    
+    /// Plaintexts live in Z_n
+    fn parse_plaintext(&self, plaintext: &[u8]) -> Result<WideUint> {
+        let m = modexp::wide_from_be_bytes(plaintext).ok_or(PokerError::InvalidPlaintext)?;
+        require!(m < self.n, PokerError::InvalidPlaintext);
+        Ok(m)
+    }
+    
+    /// Randomness must be a non-zero element of Z_n
+    fn parse_randomness(&self, randomness: &[u8]) -> Result<WideUint> {
+        let r = modexp::wide_from_be_bytes(randomness).ok_or(PokerError::InvalidEncryptionRandomness)?;
+        require!(
+            r != WideUint::ZERO && r < self.n,
+            PokerError::InvalidEncryptionRandomness
+        );
+        Ok(r)
+    }
+    
+    /// Ciphertexts are non-zero elements of Z_{n^2}, returned in Montgomery form
+    fn parse_ciphertext(&self, ciphertext: &[u8]) -> Result<WideUint> {
+        let c = modexp::wide_from_be_bytes(ciphertext).ok_or(PokerError::InvalidCiphertext)?;
+        require!(
+            c != WideUint::ZERO && c < self.mont.modulus,
+            PokerError::InvalidCiphertext
+        );
+        Ok(self.mont.to_montgomery(&c, &self.r2))
+    }
+    
+    /// Leave Montgomery form and encode at the ciphertext width
+    fn encode(&self, value: &WideUint) -> Vec<u8> {
+        let bytes = modexp::wide_to_be_bytes(&self.mont.from_montgomery(value));
+        bytes[bytes.len() - self.ciphertext_len..].to_vec()
+    }
+}
+
+/// Inputs of a disputed decryption, checked once and shared by VerificationJob and BisectionGame
+/// Both replay the square-and-multiply trace of c^λ mod n^2 over λ's bits and compare its
+/// last value with the one the accused's claim implies.
+pub struct DecryptionTrace {
+    pub mont: MontgomeryContext,
+    n: WideUint,
+    lambda: WideUint,
+    r2: WideUint,
+}
+
+impl DecryptionTrace {
+    /// Parse the accused's modulus, their revealed λ and an R^2 mod n^2 hint
+    pub fn new(modulus: &[u8], lambda: &[u8], r2: &[u8]) -> Result<Self> {
+        require!(
+            modulus.len() <= MAX_PAILLIER_N_LEN && lambda.len() <= MAX_PAILLIER_N_LEN,
+            PokerError::InvalidVerificationJob
+        );
+        let n = modexp::wide_from_be_bytes(modulus).ok_or(PokerError::InvalidVerificationJob)?;
+        let lambda = modexp::wide_from_be_bytes(lambda).ok_or(PokerError::InvalidVerificationJob)?;
+        let r2 = modexp::wide_from_be_bytes(r2).ok_or(PokerError::InvalidMontgomeryHint)?;
+        
+        // λ is only meaningful in (0, n)
+        require!(
+            lambda != WideUint::ZERO && lambda < n,
+            PokerError::InvalidVerificationJob
+        );
+        
+        let mont = MontgomeryContext::new(n.wrapping_mul(&n)).ok_or(PokerError::InvalidVerificationJob)?;
+        require!(mont.is_valid_r2(&r2), PokerError::InvalidMontgomeryHint);
+        Ok(DecryptionTrace { mont, n, lambda, r2 })
+    }
+    
+    /// Parse the trace inputs when a dispute is opened, also checking that gcd(λ, n) = 1
+    /// The accused supplies μ = λ^-1 mod n, the other half of their Paillier secret. A λ
+    /// sharing a factor with n has no inverse, so no μ can pass.
+    pub fn open(modulus: &[u8], lambda: &[u8], lambda_inverse: &[u8], r2: &[u8]) -> Result<Self> {
+        let trace = Self::new(modulus, lambda, r2)?;
+        let mu = modexp::wide_from_be_bytes(lambda_inverse).ok_or(PokerError::InvalidPaillierSecret)?;
+        require!(mu < trace.n, PokerError::InvalidPaillierSecret);
+        
+        // n·λ·μ mod n^2 = n·(λ·μ mod n), which is n exactly when λ·μ = 1 mod n
+        let n_lambda = trace.to_montgomery(&trace.n.wrapping_mul(&trace.lambda));
+        require!(
+            trace.mont.mul(&n_lambda, &mu) == trace.n,
+            PokerError::InvalidPaillierSecret
+        );
+        Ok(trace)
+    }
+    
+    /// Convert a value mod n^2 into Montgomery form
+    pub fn to_montgomery(&self, value: &WideUint) -> WideUint {
+        self.mont.to_montgomery(value, &self.r2)
+    }
+    
+    /// The disputed ciphertext in Montgomery form, the base of the trace
+    pub fn base(&self, ciphertext: &[u8]) -> Result<WideUint> {
+        let c = modexp::wide_from_be_bytes(ciphertext).ok_or(PokerError::InvalidVerificationJob)?;
+        require!(c < self.mont.modulus, PokerError::InvalidVerificationJob);
+        Ok(self.to_montgomery(&c))
+    }
+    
+    /// Last trace value (not in Montgomery form) of an honest decryption
+    /// For a plaintext m it is 1 + n·(m·λ mod n), equivalent to L(c^λ mod n^2) == m·λ mod n
+    /// without any division by n; for a dealer share it is the share itself.
+    /// None for a claim outside Z_n or Z_{n^2}, which can never be a valid decryption.
+    pub fn final_value(&self, claim: &DecryptionClaim) -> Option<WideUint> {
+        match claim {
+            DecryptionClaim::Plaintext(plaintext) => {
+                let m = match modexp::wide_from_be_bytes(plaintext) {
+                    Some(m) if plaintext.len() <= MAX_PAILLIER_N_LEN && m < self.n => m,
+                    _ => return None,
+                };
+                
+                // n·m < n^2 since m < n; a Montgomery product with λ gives n·m·λ mod n^2
+                let nm = self.to_montgomery(&self.n.wrapping_mul(&m));
+                Some(self.mont.mul(&nm, &self.lambda).wrapping_add(&WideUint::ONE))
+            }
+            DecryptionClaim::DealerShare(share) => {
+                modexp::wide_from_be_bytes(share).filter(|share| share < &self.mont.modulus)
+            }
+        }
+    }
+}
+
+/// Parse n and g, checking that n is odd with n^2 fitting in WideUint and 1 < g < n^2
+fn parse_key(pk: &PaillierPublicKey) -> Result<(WideUint, WideUint)> {
+    require!(
+        !pk.n.is_empty() && pk.n.len() <= MAX_PAILLIER_N_LEN,
+        PokerError::InvalidPaillierKey
+    );
+    let n = modexp::wide_from_be_bytes(&pk.n).ok_or(PokerError::InvalidPaillierKey)?;
+    let g = modexp::wide_from_be_bytes(&pk.g).ok_or(PokerError::InvalidPaillierKey)?;
+    
+    // n is a product of two odd primes
+    require!(
+        n > WideUint::ONE && n.as_words()[0] & 1 == 1,
+        PokerError::InvalidPaillierKey
+    );
+    require!(
+        g > WideUint::ONE && g < n.wrapping_mul(&n),
+        PokerError::InvalidPaillierKey
+    );
+    Ok((n, g))
+}
+
+impl PaillierPublicKey {
+    /// Check that a submitted key has the shape the game relies on
+    /// n must be exactly PAILLIER_N_BITS long and odd, and g must be n + 1. Only range
+    /// checks, so this is cheap enough to run whenever a key is posted.
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.n.len() == MAX_PAILLIER_N_LEN,
+            PokerError::PaillierModulusWrongSize
+        );
+        let n = modexp::wide_from_be_bytes(&self.n).ok_or(PokerError::PaillierModulusWrongSize)?;
+        require!(
+            n.bits_vartime() == PAILLIER_N_BITS,
+            PokerError::PaillierModulusWrongSize
+        );
+        require!(n.as_words()[0] & 1 == 1, PokerError::PaillierModulusEven);
+        
+        let g = modexp::wide_from_be_bytes(&self.g).ok_or(PokerError::PaillierGeneratorMismatch)?;
+        require!(
+            g == n.wrapping_add(&WideUint::ONE),
+            PokerError::PaillierGeneratorMismatch
+        );
+        Ok(())
+    }
+    
+    /// Check that a submitted ciphertext is a non-zero element of Z_{n^2}
+    /// Its length must be the byte length of n^2, the width `PaillierContext` encodes at.
+    pub fn validate_ciphertext(&self, ciphertext: &[u8]) -> Result<()> {
+        let (n, _) = parse_key(self)?;
+        let n_squared = n.wrapping_mul(&n);
+        require!(
+            ciphertext.len() == n_squared.bits_vartime().div_ceil(8),
+            PokerError::CiphertextWrongLength
+        );
+        let c = modexp::wide_from_be_bytes(ciphertext).ok_or(PokerError::CiphertextWrongLength)?;
+        require!(
+            c != WideUint::ZERO && c < n_squared,
+            PokerError::CiphertextOutOfRange
+        );
+        Ok(())
+    }
+    
+    /// Encrypt a plaintext with caller-supplied randomness
+    pub fn encrypt(&self, plaintext: &[u8], randomness: &[u8]) -> Result<Vec<u8>> {
+        PaillierContext::new(self)?.encrypt(plaintext, randomness)
+    }
+    
+    /// Add the plaintexts of two ciphertexts
+    pub fn add(&self, lhs: &[u8], rhs: &[u8]) -> Result<Vec<u8>> {
+        PaillierContext::new(self)?.add(lhs, rhs)
+    }
+    
+    /// Rerandomize a ciphertext with caller-supplied randomness
+    pub fn rerandomize(&self, ciphertext: &[u8], randomness: &[u8]) -> Result<Vec<u8>> {
+        PaillierContext::new(self)?.rerandomize(ciphertext, randomness)
+    }
+    
+    /// Check a ciphertext against a revealed plaintext and randomness
+    pub fn verify_encryption(&self, ciphertext: &[u8], plaintext: &[u8], randomness: &[u8]) -> Result<bool> {
+        PaillierContext::new(self)?.verify_encryption(ciphertext, plaintext, randomness)
+    }
+    
+    /// Check a claimed decryption against the secret λ
+    pub fn verify_decryption(&self, ciphertext: &[u8], plaintext: &[u8], lambda: &[u8]) -> Result<bool> {
+        PaillierContext::new(self)?.verify_decryption(ciphertext, plaintext, lambda)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::MAX_CIPHERTEXT_LEN;
+    use crate::test_utils::{toy_key, LAMBDA, N};
+    
+    fn decrypts_to(pk: &PaillierPublicKey, ciphertext: &[u8], m: u8) -> bool {
+        pk.verify_decryption(ciphertext, &[m], &LAMBDA.to_be_bytes()).unwrap()
+    }
+    
+    #[test]
+    fn test_encrypt_matches_definition() {
+        let pk = toy_key();
+        let ctx = PaillierContext::new(&pk).unwrap();
+        let c = ctx.encrypt(&[42], &[17]).unwrap();
+        assert_eq!(c.len(), ctx.ciphertext_len());
+        
+        // (1 + n)^m · r^n = (1 + m·n) · 17^n mod n^2
+        let n2 = N * N;
+        let mut r_n = 1u128;
+        for _ in 0..N {
+            r_n = r_n * 17 % n2 as u128;
+        }
+        let expected = ((1 + 42 * N) as u128 * r_n % n2 as u128) as u64;
+        let mut padded = [0u8; 8];
+        padded[8 - c.len()..].copy_from_slice(&c);
+        assert_eq!(u64::from_be_bytes(padded), expected);
+        
+        assert!(pk.verify_encryption(&c, &[42], &[17]).unwrap());
+        assert!(!pk.verify_encryption(&c, &[42], &[18]).unwrap());
+        assert!(decrypts_to(&pk, &c, 42));
+        assert!(!decrypts_to(&pk, &c, 43));
+    }
+    
+    #[test]
+    fn test_homomorphic_add_and_rerandomize() {
+        let pk = toy_key();
+        let c1 = pk.encrypt(&[40], &[17]).unwrap();
+        let c2 = pk.encrypt(&[2], &[99]).unwrap();
+        let sum = pk.add(&c1, &c2).unwrap();
+        assert!(decrypts_to(&pk, &sum, 42));
+        
+        let rerandomized = pk.rerandomize(&c1, &[5]).unwrap();
+        assert!(rerandomized != c1);
+        assert!(decrypts_to(&pk, &rerandomized, 40));
+    }
+    
+    #[test]
+    fn test_rejects_invalid_inputs() {
+        let pk = toy_key();
+        
+        // Even modulus, generator outside (1, n^2)
+        let even = PaillierPublicKey { n: vec![0x0c, 0xa0], g: vec![2] };
+        assert!(even.validate().is_err());
+        let bad_g = PaillierPublicKey { n: pk.n.clone(), g: vec![1] };
+        assert!(bad_g.validate().is_err());
+        assert!(PaillierPublicKey::default().validate().is_err());
+        
+        // Plaintext and randomness must be in Z_n, ciphertexts in Z_{n^2}
+        assert!(pk.encrypt(&N.to_be_bytes(), &[17]).is_err());
+        assert!(pk.encrypt(&[42], &[0]).is_err());
+        assert!(pk.add(&[0], &[1]).is_err());
+        assert!(pk.add(&(N * N).to_be_bytes(), &[1]).is_err());
+        
+        // The R^2 hint must match the key
+        assert!(PaillierContext::with_r2_hint(&pk, &[1]).is_err());
+        let r2 = PaillierContext::new(&pk).unwrap().r2;
+        assert!(PaillierContext::with_r2_hint(&pk, &modexp::wide_to_be_bytes(&r2)).is_ok());
+    }
+    
+    /// A 2048-bit odd modulus with g = n + 1 (not a real key, only its shape matters)
+    fn full_size_key() -> PaillierPublicKey {
+        let mut n = vec![0x5a; MAX_PAILLIER_N_LEN];
+        n[0] = 0xc3;
+        n[MAX_PAILLIER_N_LEN - 1] = 0x01;
+        let mut g = n.clone();
+        g[MAX_PAILLIER_N_LEN - 1] = 0x02;
+        PaillierPublicKey { n, g }
+    }
+    
+    #[test]
+    fn test_submitted_key_validation() {
+        let pk = full_size_key();
+        assert!(pk.validate().is_ok());
+        
+        // Toy keys and keys with leading zero bytes are too short
+        assert_eq!(toy_key().validate().unwrap_err(), PokerError::PaillierModulusWrongSize.into());
+        let mut short = pk.clone();
+        short.n[0] = 0;
+        assert_eq!(short.validate().unwrap_err(), PokerError::PaillierModulusWrongSize.into());
+        
+        let mut even = pk.clone();
+        even.n[MAX_PAILLIER_N_LEN - 1] = 0x02;
+        even.g[MAX_PAILLIER_N_LEN - 1] = 0x03;
+        assert_eq!(even.validate().unwrap_err(), PokerError::PaillierModulusEven.into());
+        
+        let mut bad_g = pk.clone();
+        bad_g.g = vec![2];
+        assert_eq!(bad_g.validate().unwrap_err(), PokerError::PaillierGeneratorMismatch.into());
+    }
+    
+    #[test]
+    fn test_submitted_ciphertext_validation() {
+        let pk = full_size_key();
+        let mut c = vec![0u8; MAX_CIPHERTEXT_LEN];
+        c[MAX_CIPHERTEXT_LEN - 1] = 7;
+        assert!(pk.validate_ciphertext(&c).is_ok());
+        
+        assert_eq!(pk.validate_ciphertext(&c[1..]).unwrap_err(), PokerError::CiphertextWrongLength.into());
+        assert_eq!(pk.validate_ciphertext(&[0u8; MAX_CIPHERTEXT_LEN]).unwrap_err(), PokerError::CiphertextOutOfRange.into());
+        assert_eq!(pk.validate_ciphertext(&[0xff; MAX_CIPHERTEXT_LEN]).unwrap_err(), PokerError::CiphertextOutOfRange.into());
+        
+        // Ciphertexts under the toy key are exactly as wide as n^2
+        let toy = toy_key();
+        let c = toy.encrypt(&[42], &[17]).unwrap();
+        assert!(toy.validate_ciphertext(&c).is_ok());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::state::Card;
+use crate::state::{Card, HandRank};
 
 /// Evaluates a 5-card poker hand and returns a score
 /// Higher score = better hand
@@ -41,10 +41,10 @@ pub fn evaluate_hand(cards: &[u8; 5]) -> u32 {
     let three = rank_counts.iter().find(|(_, count)| *count == 3);
     let pair = rank_counts.iter().find(|(_, count)| *count == 2);
 
-    if three.is_some() && pair.is_some() {
+    if let (Some(three), Some(pair)) = (three, pair) {
         // Full House
-        let three_rank = three.unwrap().0 + 2;
-        let pair_rank = pair.unwrap().0 + 2;
+        let three_rank = three.0 + 2;
+        let pair_rank = pair.0 + 2;
         return 6 << 20 | (three_rank as u32) << 16 | (pair_rank as u32) << 12;
     }
 
@@ -63,9 +63,9 @@ pub fn evaluate_hand(cards: &[u8; 5]) -> u32 {
         return 4 << 20 | (high_card as u32) << 16;
     }
 
-    if three.is_some() {
+    if let Some(three) = three {
         // Three of a Kind
-        let three_rank = three.unwrap().0 + 2;
+        let three_rank = three.0 + 2;
         let kickers: Vec<u8> = rank_counts
             .iter()
             .filter(|(_, count)| *count == 1)
@@ -156,6 +156,22 @@ pub fn find_best_hand(hole_cards: &[u8; 2], community_cards: &[u8; 5]) -> ([u8;
     (best_hand, best_score)
 }
 
+/// Maps a score from `evaluate_hand` back to its hand category
+pub fn hand_rank(score: u32) -> HandRank {
+    match score >> 20 {
+        9 => HandRank::RoyalFlush,
+        8 => HandRank::StraightFlush,
+        7 => HandRank::FourOfAKind,
+        6 => HandRank::FullHouse,
+        5 => HandRank::Flush,
+        4 => HandRank::Straight,
+        3 => HandRank::ThreeOfAKind,
+        2 => HandRank::TwoPair,
+        1 => HandRank::OnePair,
+        _ => HandRank::HighCard,
+    }
+}
+
 fn check_straight(ranks: &[u8]) -> bool {
     // Check normal straight
     if ranks[0] == ranks[1] + 1
@@ -167,7 +183,7 @@ fn check_straight(ranks: &[u8]) -> bool {
     }
 
     // Check wheel (A-2-3-4-5)
-    if ranks == &[12, 3, 2, 1, 0] {
+    if ranks == [12, 3, 2, 1, 0] {
         return true;
     }
 
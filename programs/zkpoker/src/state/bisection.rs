@@ -0,0 +1,310 @@
+use anchor_lang::prelude::*;
+use crate::errors::PokerError;
+use crate::modexp::{self, MontgomeryContext};
+use crate::paillier::DecryptionTrace;
+use super::types::*;
+
+/// Interactive bisection game over a disputed Paillier decryption
+/// Instead of recomputing c^λ mod n^2 on-chain, the accused and the challenger narrow
+/// the square-and-multiply trace down to a single step, which is then checked directly.
+/// Checkpoint k is the accumulator after processing the top k bits of λ: checkpoint 0 is
+/// 1 and the last checkpoint is fixed by the accused's claim: 1 + n·(m·λ mod n) for a
+/// plaintext m, or the share itself for a dealer share.
+#[account]
+pub struct BisectionGame {
+    /// Game, hand and deck position under dispute
+    pub game: Pubkey,
+    pub hand_id: u64,
+    pub card_index: u8,
+    pub accused_index: u8,
+    pub challenger_index: u8,
+    
+    /// Account that paid for the game
+    pub payer: Pubkey,
+    
+    pub status: VerificationStatus,
+    
+    /// Paillier modulus n and the secret λ revealed by the accused
+    pub modulus: Vec<u8>,
+    pub exponent: Vec<u8>,
+    
+    /// R^2 mod n^2 hint, checked when the game is opened
+    pub r2: Vec<u8>,
+    
+    /// Ciphertext in Montgomery form mod n^2
+    pub base: Vec<u8>,
+    
+    /// Agreed checkpoint and disputed checkpoint, values in Montgomery form mod n^2
+    pub lo: u16,
+    pub lo_value: Vec<u8>,
+    pub hi: u16,
+    pub hi_value: Vec<u8>,
+    
+    /// Accused's claim for the midpoint checkpoint, awaiting the challenger's answer
+    pub mid_value: Option<Vec<u8>>,
+    
+    /// Number of completed bisection rounds
+    pub rounds: u16,
+    
+    pub bump: u8,
+}
+
+impl BisectionGame {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // game
+        8 + // hand_id
+        1 + // card_index
+        1 + // accused_index
+        1 + // challenger_index
+        32 + // payer
+        1 + // status
+        (4 + MAX_PAILLIER_N_LEN) + // modulus
+        (4 + MAX_PAILLIER_N_LEN) + // exponent
+        (4 + modexp::WIDE_UINT_BYTES) + // r2
+        (4 + modexp::WIDE_UINT_BYTES) + // base
+        2 + // lo
+        (4 + modexp::WIDE_UINT_BYTES) + // lo_value
+        2 + // hi
+        (4 + modexp::WIDE_UINT_BYTES) + // hi_value
+        (1 + 4 + modexp::WIDE_UINT_BYTES) + // mid_value
+        2 + // rounds
+        1; // bump
+    
+    /// Load the disputed decryption and set up the full trace as the disputed segment
+    /// `lambda_inverse` is μ = λ^-1 mod n, proving that the revealed λ is coprime to n.
+    pub fn start(
+        &mut self,
+        modulus: &[u8],
+        exponent: Vec<u8>,
+        lambda_inverse: &[u8],
+        ciphertext: &[u8],
+        claim: &DecryptionClaim,
+        r2: Vec<u8>,
+    ) -> Result<()> {
+        let trace = DecryptionTrace::open(modulus, &exponent, lambda_inverse, &r2)?;
+        
+        self.modulus = modulus.to_vec();
+        self.base = modexp::wide_to_be_bytes(&trace.base(ciphertext)?);
+        self.lo = 0;
+        self.lo_value = modexp::wide_to_be_bytes(&trace.mont.one());
+        self.hi = modexp::be_bit_len(&exponent) as u16;
+        self.mid_value = None;
+        self.rounds = 0;
+        self.exponent = exponent;
+        self.r2 = r2;
+        
+        // The accused stands by the honest final value as the last checkpoint
+        let final_value = match trace.final_value(claim) {
+            Some(value) => value,
+            None => {
+                self.status = VerificationStatus::Invalid;
+                return Ok(());
+            }
+        };
+        self.hi_value = modexp::wide_to_be_bytes(&trace.to_montgomery(&final_value));
+        self.status = VerificationStatus::InProgress;
+        
+        self.resolve_if_single_step(&trace.mont)
+    }
+    
+    /// Check whether the accused has to post the next midpoint
+    pub fn awaiting_midpoint(&self) -> bool {
+        self.status == VerificationStatus::InProgress && self.mid_value.is_none()
+    }
+    
+    /// Midpoint checkpoint of the disputed segment
+    pub fn midpoint(&self) -> u16 {
+        self.lo + (self.hi - self.lo) / 2
+    }
+    
+    /// Record the accused's claimed value (mod n^2, not in Montgomery form) at the midpoint
+    pub fn post_midpoint(&mut self, value: &[u8]) -> Result<()> {
+        require!(self.awaiting_midpoint(), PokerError::InvalidBisectionMove);
+        
+        let ctx = self.context()?;
+        let value = modexp::wide_from_be_bytes(value).ok_or(PokerError::InvalidBisectionMove)?;
+        require!(value < ctx.modulus, PokerError::InvalidBisectionMove);
+        let r2 = modexp::wide_from_be_bytes(&self.r2).ok_or(PokerError::InvalidMontgomeryHint)?;
+        
+        self.mid_value = Some(modexp::wide_to_be_bytes(&ctx.to_montgomery(&value, &r2)));
+        Ok(())
+    }
+    
+    /// Narrow the disputed segment to the half the challenger disagrees with
+    /// Agreeing with the midpoint moves the dispute into the upper half.
+    pub fn choose(&mut self, agree_with_midpoint: bool) -> Result<()> {
+        require!(
+            self.status == VerificationStatus::InProgress,
+            PokerError::VerificationComplete
+        );
+        let mid_value = self.mid_value.take().ok_or(PokerError::InvalidBisectionMove)?;
+        
+        let mid = self.midpoint();
+        if agree_with_midpoint {
+            self.lo = mid;
+            self.lo_value = mid_value;
+        } else {
+            self.hi = mid;
+            self.hi_value = mid_value;
+        }
+        self.rounds += 1;
+        
+        let ctx = self.context()?;
+        self.resolve_if_single_step(&ctx)
+    }
+    
+    fn context(&self) -> Result<MontgomeryContext> {
+        let n = modexp::wide_from_be_bytes(&self.modulus).ok_or(PokerError::InvalidVerificationJob)?;
+        Ok(MontgomeryContext::new(n.wrapping_mul(&n)).ok_or(PokerError::InvalidVerificationJob)?)
+    }
+    
+    /// Once the segment is a single square-and-multiply step, check it directly
+    fn resolve_if_single_step(&mut self, ctx: &MontgomeryContext) -> Result<()> {
+        if self.hi - self.lo > 1 {
+            return Ok(());
+        }
+        
+        let base = modexp::wide_from_be_bytes(&self.base).ok_or(PokerError::InvalidVerificationJob)?;
+        let lo_value = modexp::wide_from_be_bytes(&self.lo_value).ok_or(PokerError::InvalidVerificationJob)?;
+        let hi_value = modexp::wide_from_be_bytes(&self.hi_value).ok_or(PokerError::InvalidVerificationJob)?;
+        
+        // Going from checkpoint lo to lo + 1 processes bit (T - 1 - lo) of λ
+        let bit_count = modexp::be_bit_len(&self.exponent) as u16;
+        let bit = modexp::be_bit(&self.exponent, (bit_count - 1 - self.lo) as usize);
+        
+        self.status = if ctx.pow_step(&lo_value, &base, bit) == hi_value {
+            VerificationStatus::Valid
+        } else {
+            VerificationStatus::Invalid
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modexp::WideUint;
+    use crate::test_utils::{encrypt, n_squared, pow, r2_hint, LAMBDA, LAMBDA_INVERSE, N};
+    
+    /// Honest checkpoint k: c raised to the top k bits of λ
+    fn checkpoint(c: &WideUint, k: u16) -> WideUint {
+        let bit_count = 64 - LAMBDA.leading_zeros() as u16;
+        pow(c, LAMBDA >> (bit_count - k))
+    }
+    
+    fn new_game() -> BisectionGame {
+        BisectionGame {
+            game: Pubkey::default(),
+            hand_id: 1,
+            card_index: 4,
+            accused_index: 0,
+            challenger_index: 1,
+            payer: Pubkey::default(),
+            status: VerificationStatus::InProgress,
+            modulus: vec![],
+            exponent: vec![],
+            r2: vec![],
+            base: vec![],
+            lo: 0,
+            lo_value: vec![],
+            hi: 0,
+            hi_value: vec![],
+            mid_value: None,
+            rounds: 0,
+            bump: 0,
+        }
+    }
+    
+    fn start(c: &WideUint, claim: DecryptionClaim) -> BisectionGame {
+        let mut bisection = new_game();
+        let r2 = modexp::wide_to_be_bytes(&r2_hint(&n_squared()));
+        bisection
+            .start(
+                &N.to_be_bytes(),
+                LAMBDA.to_be_bytes().to_vec(),
+                &LAMBDA_INVERSE.to_be_bytes(),
+                &modexp::wide_to_be_bytes(c),
+                &claim,
+                r2,
+            )
+            .unwrap();
+        bisection
+    }
+    
+    fn plaintext(m: u64) -> DecryptionClaim {
+        DecryptionClaim::Plaintext(m.to_be_bytes().to_vec())
+    }
+    
+    /// Play the game to the end; the accused posts `accused_value` for each midpoint and
+    /// an honest challenger agrees exactly when the midpoint matches the real trace
+    fn play(
+        bisection: &mut BisectionGame,
+        c: &WideUint,
+        accused_value: impl Fn(u16) -> WideUint,
+    ) -> VerificationStatus {
+        while bisection.status == VerificationStatus::InProgress {
+            let mid = bisection.midpoint();
+            let value = accused_value(mid);
+            bisection.post_midpoint(&modexp::wide_to_be_bytes(&value)).unwrap();
+            bisection.choose(value == checkpoint(c, mid)).unwrap();
+        }
+        bisection.status
+    }
+    
+    #[test]
+    fn test_honest_accused_wins_bisection() {
+        let c = encrypt(42, 17);
+        let mut bisection = start(&c, plaintext(42));
+        assert!(play(&mut bisection, &c, |k| checkpoint(&c, k)) == VerificationStatus::Valid);
+        
+        // log2 of the 10-bit trace, rounded up
+        assert_eq!(bisection.rounds, 4);
+        assert!(bisection.choose(true).is_err());
+    }
+    
+    #[test]
+    fn test_honest_dealer_wins_bisection() {
+        // The dealer's share is the λ-layer of the card, a full-width value mod n^2
+        let c = encrypt(42, 17);
+        let share = DecryptionClaim::DealerShare(modexp::wide_to_be_bytes(&pow(&c, LAMBDA)));
+        let mut bisection = start(&c, share);
+        assert!(play(&mut bisection, &c, |k| checkpoint(&c, k)) == VerificationStatus::Valid);
+        
+        let forged = DecryptionClaim::DealerShare(modexp::wide_to_be_bytes(&pow(&c, LAMBDA + 1)));
+        let mut bisection = start(&c, forged);
+        assert!(play(&mut bisection, &c, |k| checkpoint(&c, k)) == VerificationStatus::Invalid);
+    }
+    
+    #[test]
+    fn test_lying_accused_is_caught() {
+        let c = encrypt(42, 17);
+        
+        // Claiming the wrong plaintext forces a wrong checkpoint somewhere in the trace,
+        // whether the accused follows the real trace or not
+        let mut bisection = start(&c, plaintext(43));
+        assert!(play(&mut bisection, &c, |k| checkpoint(&c, k)) == VerificationStatus::Invalid);
+        
+        let mut bisection = start(&c, plaintext(43));
+        let forged = |k: u16| checkpoint(&c, k).wrapping_add(&WideUint::ONE);
+        assert!(play(&mut bisection, &c, forged) == VerificationStatus::Invalid);
+        
+        // Plaintexts outside Z_n fail immediately
+        let bisection = start(&c, plaintext(N + 42));
+        assert!(bisection.status == VerificationStatus::Invalid);
+    }
+    
+    #[test]
+    fn test_moves_out_of_turn_rejected() {
+        let c = encrypt(42, 17);
+        let mut bisection = start(&c, plaintext(42));
+        
+        // The challenger cannot answer before a midpoint is posted
+        assert!(bisection.choose(true).is_err());
+        
+        // Midpoint values must be reduced mod n^2, and only one can be pending
+        assert!(bisection.post_midpoint(&modexp::wide_to_be_bytes(&n_squared())).is_err());
+        bisection.post_midpoint(&[1]).unwrap();
+        assert!(bisection.post_midpoint(&[1]).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use super::types::*;
+use super::game::GameError;
+
+/// On-chain copy of a hand's encrypted decks
+/// Ciphertexts are too large to fit both decks in a single transaction, so the players
+/// upload them card by card. Every card is checked against the committed Merkle root,
+/// which makes the buffer a data-availability guarantee for the deck.
+#[account]
+pub struct DeckBuffer {
+    /// Game and hand this buffer belongs to
+    pub game: Pubkey,
+    pub hand_id: u64,
+    
+    /// Account that paid for the buffer and receives the rent on close
+    pub payer: Pubkey,
+    
+    /// Bitmask of uploaded deck positions, indexed by DeckKind
+    pub received: [u64; 2],
+    
+    /// Uploaded cards in arrival order, indexed by DeckKind
+    pub cards: [Vec<BufferedCard>; 2],
+    
+    pub bump: u8,
+}
+
+/// An uploaded encrypted card
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct BufferedCard {
+    pub card_index: u8,
+    pub ciphertext: Vec<u8>,
+}
+
+impl BufferedCard {
+    /// Space taken by a card with a ciphertext of the given length
+    pub const fn space(ciphertext_len: usize) -> usize {
+        1 + // card_index
+            4 + ciphertext_len // ciphertext
+    }
+}
+
+impl DeckBuffer {
+    /// Space of an empty buffer; it grows as cards are appended
+    pub const BASE_LEN: usize = 8 + // discriminator
+        32 + // game
+        8 + // hand_id
+        32 + // payer
+        (8 * 2) + // received
+        (4 * 2) + // cards (empty vectors)
+        1; // bump
+    
+    /// Bitmask with every deck position set
+    pub const FULL_DECK_MASK: u64 = (1u64 << DECK_SIZE) - 1;
+    
+    /// Extra space needed to append the given cards
+    pub fn chunk_space(cards: &[CommittedCard]) -> usize {
+        cards.iter().map(|card| BufferedCard::space(card.ciphertext.len())).sum()
+    }
+    
+    /// Check whether a deck position has been uploaded
+    pub fn has_card(&self, deck: DeckKind, card_index: u8) -> bool {
+        card_index < DECK_SIZE && self.received[deck as usize] & (1u64 << card_index) != 0
+    }
+    
+    /// Check whether every card of a deck has been uploaded
+    pub fn is_complete(&self, deck: DeckKind) -> bool {
+        self.received[deck as usize] == Self::FULL_DECK_MASK
+    }
+    
+    /// Get the uploaded ciphertext at a deck position
+    pub fn card(&self, deck: DeckKind, card_index: u8) -> Option<&[u8]> {
+        self.cards[deck as usize]
+            .iter()
+            .find(|card| card.card_index == card_index)
+            .map(|card| card.ciphertext.as_slice())
+    }
+    
+    /// Store a card that has already been checked against the deck commitment
+    pub fn append_card(&mut self, deck: DeckKind, card_index: u8, ciphertext: Vec<u8>) -> Result<()> {
+        require!(card_index < DECK_SIZE, GameError::InvalidCardPosition);
+        require!(
+            !ciphertext.is_empty() && ciphertext.len() <= MAX_CIPHERTEXT_LEN,
+            GameError::CiphertextTooLarge
+        );
+        require!(!self.has_card(deck, card_index), GameError::CardAlreadyUploaded);
+        
+        self.received[deck as usize] |= 1u64 << card_index;
+        self.cards[deck as usize].push(BufferedCard { card_index, ciphertext });
+        Ok(())
+    }
+}
@@ -1,5 +1,13 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use solana_zk_token_sdk::curve25519::scalar::PodScalar;
+use crate::elgamal::{self, ElGamalCiphertext};
+use crate::engine::{BettingState, HandEngine};
+use crate::errors::PokerError;
+use crate::merkle;
+use crate::zk::Groth16VerifyingKey;
 use super::types::*;
+use super::verifying_key::VerifyingKeyAccount;
 
 /// The main Game account - persists across multiple hands
 /// This is a PDA that stores the long-running match state between two players
@@ -8,12 +16,24 @@ pub struct Game {
     /// The two players in this game
     pub players: [Pubkey; 2],
     
+    /// Card encryption scheme for this match, chosen by the creator
+    pub card_backend: CardBackend,
+    
     /// Paillier public keys for each player (set once at game creation/join)
     pub paillier_pks: [PaillierPublicKey; 2],
     
+    /// ElGamal public keys for each player, used instead in ElGamal matches
+    pub elgamal_pks: [[u8; 32]; 2],
+    
     /// Player chip stacks (persist across hands)
     pub player_stacks: [u64; 2],
     
+    /// Misbehaviour bonds posted by each player on entry (10% of the stake)
+    pub player_bonds: [u64; 2],
+    
+    /// Game identifier chosen by the creator (used in PDA seeds)
+    pub game_id: u64,
+    
     /// Current hand number (increments with each new hand)
     pub current_hand_id: u64,
     
@@ -31,6 +51,15 @@ pub struct Game {
     /// Timing configuration
     pub action_timeout: i64, // seconds
     
+    /// Hash used for the deck Merkle commitments, fixed at game creation
+    pub merkle_hash: MerkleHash,
+    
+    /// Whether reshuffle proofs are verified in join_hand or only when disputed
+    pub shuffle_verification: ShuffleVerification,
+    
+    /// Verifying key version per circuit, pinned from the registry at game creation
+    pub vk_versions: [u32; PROOF_CIRCUIT_COUNT],
+    
     /// Invite-only game (if Some, only this pubkey can join)
     pub invited_opponent: Option<Pubkey>,
     
@@ -68,6 +97,8 @@ pub struct HandState {
     pub pot: u64,
     pub bets: [u64; 2], // Current bets for each player in this round
     pub betting_round: BettingRound,
+    pub last_raise_size: u64, // Size of the last full bet or raise this round, the minimum raise
+    pub last_aggressor: Option<u8>, // Player who made the last full bet or raise this round
     
     /// Revealed cards during this hand
     /// Stores (card_index, partially_decrypted_card_data) tuples
@@ -89,10 +120,13 @@ pub struct HandState {
     pub dispute_active: bool,
     pub challenger_index: u8, // 0 or 1
     pub disputed_action: DisputedAction,
+    pub pre_dispute_stage: HandStage, // Stage to resume if the dispute fails
+    pub pre_dispute_turn_index: u8,
     
     /// Player flags for this hand
     pub player_folded: [bool; 2],
     pub player_all_in: [bool; 2],
+    pub has_acted: [bool; 2], // Acted in the current betting round; blinds don't count
     pub player_revealed_showdown: [bool; 2],
     
     /// Timing
@@ -118,21 +152,86 @@ pub struct StoredProof {
 pub enum ProofType {
     /// Mandatory proof: deck contains 52 unique cards (verified immediately)
     DeckCreation,
-    /// Optimistic: deck was correctly reshuffled and re-encrypted
+    /// Deck was correctly reshuffled and re-encrypted (optimistic unless the match
+    /// verifies shuffles immediately)
     Reshuffle,
-    /// Optimistic: card was correctly decrypted (specify card index)
+    /// Optimistic: the dealer's decryption share of a card is correct (specify card index)
     CardDecryption { card_index: u8 },
-    /// Optimistic: pocket cards were correctly revealed at showdown
-    ShowdownReveal { player_index: u8 },
+}
+
+impl ProofType {
+    /// Circuit the proof is verified against
+    pub fn circuit(&self) -> ProofCircuit {
+        match self {
+            ProofType::DeckCreation => ProofCircuit::DeckCreation,
+            ProofType::Reshuffle => ProofCircuit::Reshuffle,
+            ProofType::CardDecryption { .. } => ProofCircuit::CardDecryption,
+        }
+    }
+}
+
+impl DisputedAction {
+    /// The stored proof type a dispute of this action is checked against
+    pub fn proof_type(&self) -> Option<ProofType> {
+        match *self {
+            DisputedAction::None | DisputedAction::CardDecryptionBisection { .. } => None,
+            DisputedAction::DeckCreation => Some(ProofType::DeckCreation),
+            DisputedAction::Reshuffle => Some(ProofType::Reshuffle),
+            DisputedAction::CardDecryption { card_index } => {
+                Some(ProofType::CardDecryption { card_index })
+            }
+        }
+    }
+}
+
+impl StoredProof {
+    pub const LEN: usize = 2 + // proof_type (tag + card/player index)
+        1 + // submitter_index
+        (4 + MAX_PROOF_LEN) + // proof
+        8; // submitted_at
+}
+
+impl HandState {
+    pub const LEN: usize = 1 + // stage
+        1 + // dealer_index
+        1 + // current_turn_index
+        8 + // action_deadline
+        32 + // deck_merkle_root
+        32 + // doubly_encrypted_deck_merkle_root
+        8 + // pot
+        (8 * 2) + // bets
+        1 + // betting_round
+        8 + // last_raise_size
+        (1 + 1) + // last_aggressor
+        (1 + 1 + 4 + MAX_CIPHERTEXT_LEN) * 9 + // revealed_cards
+        (1 + 1) * 5 + // community_cards
+        (1 + 2) * 2 + // pocket_cards
+        (1 + StoredProof::LEN) * 20 + // stored_proofs
+        1 + // dispute_active
+        1 + // challenger_index
+        2 + // disputed_action (tag + index)
+        1 + // pre_dispute_stage
+        1 + // pre_dispute_turn_index
+        2 + // player_folded
+        2 + // player_all_in
+        2 + // has_acted
+        2 + // player_revealed_showdown
+        8 + // hand_started_at
+        8 + // last_action_at
+        (1 + 1) + // winner
+        (1 + 1); // winning_hand_rank
 }
 
 impl Game {
-    /// Calculate space needed for Game account
-    /// Note: This is a rough estimate, actual size will vary based on Vec lengths
-    pub const BASE_LEN: usize = 8 + // discriminator
+    /// Space needed for the Game account with every variable-length field at its maximum
+    pub const LEN: usize = 8 + // discriminator
         (32 * 2) + // players
-        (4 + 256 + 4 + 257) * 2 + // paillier_pks (rough estimate for Vec<u8>)
+        1 + // card_backend
+        (4 + MAX_PAILLIER_N_LEN + 4 + MAX_PAILLIER_G_LEN) * 2 + // paillier_pks
+        (32 * 2) + // elgamal_pks
         (8 * 2) + // player_stacks
+        (8 * 2) + // player_bonds
+        8 + // game_id
         8 + // current_hand_id
         1 + // game_status
         32 + // token_vault
@@ -140,16 +239,27 @@ impl Game {
         8 + // small_blind
         8 + // big_blind
         8 + // action_timeout
+        1 + // merkle_hash
+        1 + // shuffle_verification
+        4 * PROOF_CIRCUIT_COUNT + // vk_versions
         (1 + 32) + // invited_opponent (Option<Pubkey>)
+        HandState::LEN + // hand
         1 + // bump
-        8 + // last_action_timestamp
-        4096; // HandState (we'll allocate a large buffer for the embedded state)
+        8; // last_action_timestamp
+    
+    /// Accounts created through CPI are capped at 10KB, so the Game account is
+    /// created at this size and grown to `Game::LEN` when the second player joins
+    pub const INIT_LEN: usize = if Game::LEN < MAX_PERMITTED_DATA_INCREASE {
+        Game::LEN
+    } else {
+        MAX_PERMITTED_DATA_INCREASE
+    };
     
     /// Initialize a new hand within this game
     pub fn init_new_hand(&mut self, clock: &Clock) {
         // Rotate dealer
         let new_dealer_index = if self.current_hand_id == 0 {
-            0 // First hand, player 0 is dealer
+            1 // First hand, the joining player deals and the creator commits the deck
         } else {
             1 - self.hand.dealer_index // Alternate dealer
         };
@@ -167,15 +277,20 @@ impl Game {
             pot: 0,
             bets: [0, 0],
             betting_round: BettingRound::PreFlop,
-            revealed_cards: [None; 9],
+            last_raise_size: 0,
+            last_aggressor: None,
+            revealed_cards: Default::default(),
             community_cards: [None; 5],
             pocket_cards: [None; 2],
-            stored_proofs: [None; 20],
+            stored_proofs: Default::default(),
             dispute_active: false,
             challenger_index: 0,
             disputed_action: DisputedAction::None,
+            pre_dispute_stage: HandStage::WaitingForHandCreation,
+            pre_dispute_turn_index: 0,
             player_folded: [false, false],
             player_all_in: [false, false],
+            has_acted: [false, false],
             player_revealed_showdown: [false, false],
             hand_started_at: clock.unix_timestamp,
             last_action_at: clock.unix_timestamp,
@@ -217,69 +332,383 @@ impl Game {
         clock.unix_timestamp > self.hand.last_action_at + self.action_timeout
     }
     
-    /// Post blinds at the start of a hand
-    pub fn post_blinds(&mut self) -> Result<()> {
-        let dealer_index = self.hand.dealer_index as usize;
-        let non_dealer_index = (1 - self.hand.dealer_index) as usize;
-        
-        // In heads-up: dealer posts small blind, non-dealer posts big blind
+    /// Record that an action happened now and restart the action clock
+    pub fn mark_action(&mut self, clock: &Clock) {
+        self.hand.last_action_at = clock.unix_timestamp;
+        self.hand.action_deadline = clock.unix_timestamp + self.action_timeout;
+        self.last_action_timestamp = clock.unix_timestamp;
+    }
+    
+    /// Index of the non-dealer for the current hand
+    pub fn non_dealer_index(&self) -> u8 {
+        1 - self.hand.dealer_index
+    }
+    
+    /// Deck positions of a player's pocket cards for the current hand
+    pub fn pocket_card_indices(&self, player_index: u8) -> [u8; 2] {
+        if player_index == self.hand.dealer_index {
+            DEALER_POCKET_INDICES
+        } else {
+            NON_DEALER_POCKET_INDICES
+        }
+    }
+    
+    /// Board position (0-4) of a community card's deck position
+    pub fn community_card_position(card_index: u8) -> Option<usize> {
+        match card_index {
+            index if FLOP_CARD_INDICES.contains(&index) => {
+                Some((index - FLOP_CARD_INDICES[0]) as usize)
+            }
+            TURN_CARD_INDEX => Some(3),
+            RIVER_CARD_INDEX => Some(4),
+            _ => None,
+        }
+    }
+    
+    /// Number of board cards dealt so far
+    pub fn dealt_board_cards(&self) -> usize {
+        self.hand.community_cards.iter().take_while(|card| card.is_some()).count()
+    }
+    
+    /// Deck positions of the board cards not dealt yet, revealed together in an all-in runout
+    pub fn undealt_board_indices(&self) -> &'static [u8] {
+        &BOARD_CARD_INDICES[self.dealt_board_cards()..]
+    }
+    
+    /// Find a partially decrypted card submitted for the given deck position
+    pub fn find_revealed_card(&self, card_index: u8) -> Option<&PartiallyDecryptedCard> {
+        self.hand
+            .revealed_cards
+            .iter()
+            .flatten()
+            .find(|(index, _)| *index == card_index)
+            .map(|(_, card)| card)
+    }
+    
+    /// Whether a player's decryption of the given deck position is on chain and can be disputed
+    /// The dealer decrypts first with their share, the non-dealer's decryption is the finalized
+    /// community card, so a reveal still waiting for step 2 holds nothing to accuse them of.
+    pub fn has_submitted_decryption(&self, player_index: u8, card_index: u8) -> bool {
+        if player_index == self.hand.dealer_index {
+            self.find_revealed_card(card_index).is_some()
+        } else {
+            Self::community_card_position(card_index)
+                .is_some_and(|position| self.hand.community_cards[position].is_some())
+        }
+    }
+    
+    /// Player who has to upload the given deck (the one who committed to it)
+    pub fn deck_uploader_index(&self, deck: DeckKind) -> u8 {
+        match deck {
+            DeckKind::Initial => self.non_dealer_index(),
+            DeckKind::Reshuffled => self.hand.dealer_index,
+        }
+    }
+    
+    /// Merkle root the given deck was committed to
+    pub fn deck_root(&self, deck: DeckKind) -> [u8; 32] {
+        match deck {
+            DeckKind::Initial => self.hand.deck_merkle_root,
+            DeckKind::Reshuffled => self.hand.doubly_encrypted_deck_merkle_root,
+        }
+    }
+    
+    /// Store a player's card key, which must match the match's backend
+    pub fn set_card_key(&mut self, player_index: u8, key: CardPublicKey) -> Result<()> {
+        require!(key.backend() == self.card_backend, PokerError::CardBackendMismatch);
+        key.validate()?;
+        match key {
+            CardPublicKey::Paillier(pk) => self.paillier_pks[player_index as usize] = pk,
+            CardPublicKey::ElGamal(pk) => self.elgamal_pks[player_index as usize] = pk,
+        }
+        Ok(())
+    }
+    
+    /// Check an uploaded deck card is a well-formed ciphertext
+    /// Paillier decks are encrypted under their uploader's key, ElGamal decks under the
+    /// players' joint key.
+    pub fn validate_deck_ciphertext(&self, deck: DeckKind, ciphertext: &[u8]) -> Result<()> {
+        match self.card_backend {
+            CardBackend::Paillier => {
+                self.paillier_pks[self.deck_uploader_index(deck) as usize].validate_ciphertext(ciphertext)
+            }
+            CardBackend::ElGamalRistretto => ElGamalCiphertext::from_bytes(ciphertext).map(|_| ()),
+        }
+    }
+    
+    /// Check a dealer decryption share is well-formed
+    /// Paillier shares are still encrypted under the non-dealer's key; ElGamal shares are
+    /// the point s·c1.
+    pub fn validate_decryption_share(&self, share: &[u8]) -> Result<()> {
+        match self.card_backend {
+            CardBackend::Paillier => {
+                self.paillier_pks[self.non_dealer_index() as usize].validate_ciphertext(share)
+            }
+            CardBackend::ElGamalRistretto => elgamal::parse_point(share).map(|_| ()),
+        }
+    }
+    
+    /// Check a player's ElGamal decryption shares of revealed cards, one proof per card
+    /// Returns the cards' ciphertexts, which each proof carries and which must match the
+    /// revealed digests (already checked against the deck commitment).
+    pub fn verify_decryption_shares(
+        &self,
+        player_index: u8,
+        revealed: &CardMultiproof,
+        shares: &[EncryptedCard],
+        proofs: &[DecryptionShareProof],
+    ) -> Result<Vec<ElGamalCiphertext>> {
         require!(
-            self.player_stacks[dealer_index] >= self.small_blind,
-            GameError::InsufficientStack
+            self.card_backend == CardBackend::ElGamalRistretto,
+            PokerError::UnsupportedCardBackend
         );
         require!(
-            self.player_stacks[non_dealer_index] >= self.big_blind,
-            GameError::InsufficientStack
+            shares.len() == revealed.cards.len() && proofs.len() == revealed.cards.len(),
+            PokerError::MissingDecryptionShares
         );
         
-        // Deduct blinds from stacks
-        self.player_stacks[dealer_index] -= self.small_blind;
-        self.player_stacks[non_dealer_index] -= self.big_blind;
+        let public_key = elgamal::parse_point(&self.elgamal_pks[player_index as usize])?;
+        revealed
+            .cards
+            .iter()
+            .zip(shares.iter().zip(proofs))
+            .map(|(card, (share, proof))| {
+                require!(
+                    self.merkle_hash.digest(&proof.ciphertext) == card.ciphertext_digest,
+                    PokerError::InvalidMerkleProof
+                );
+                let ciphertext = ElGamalCiphertext::from_bytes(&proof.ciphertext)?;
+                elgamal::verify_decryption_share(
+                    &public_key,
+                    &ciphertext,
+                    &elgamal::parse_point(&share.data)?,
+                    &PodScalar(proof.challenge),
+                    &PodScalar(proof.response),
+                )?;
+                Ok(ciphertext)
+            })
+            .collect()
+    }
+    
+    /// Decrypt ElGamal cards from the dealer's stored shares and the non-dealer's shares
+    pub fn decrypt_revealed_cards(
+        &self,
+        card_indices: &[u8],
+        ciphertexts: &[ElGamalCiphertext],
+        shares: &[EncryptedCard],
+    ) -> Result<Vec<u8>> {
+        card_indices
+            .iter()
+            .zip(ciphertexts.iter().zip(shares))
+            .map(|(card_index, (ciphertext, share))| {
+                let dealer_share = self
+                    .find_revealed_card(*card_index)
+                    .ok_or(PokerError::MissingDecryptionShares)?;
+                let plaintext = elgamal::combine_shares(
+                    ciphertext,
+                    &[elgamal::parse_point(&dealer_share.data)?, elgamal::parse_point(&share.data)?],
+                )?;
+                Ok(elgamal::decode_card(&plaintext).ok_or(PokerError::InvalidCommunityCards)?)
+            })
+            .collect()
+    }
+    
+    /// Decrypt a player's ElGamal pocket cards at showdown from both players' proven shares
+    pub fn decrypt_pocket_cards(
+        &self,
+        player_index: u8,
+        revealed: &CardMultiproof,
+        pocket_shares: &[PocketCardShares],
+    ) -> Result<[u8; 2]> {
+        require!(
+            self.card_backend == CardBackend::ElGamalRistretto,
+            PokerError::UnsupportedCardBackend
+        );
+        require!(
+            revealed.cards.len() == 2 && pocket_shares.len() == 2,
+            PokerError::MissingDecryptionShares
+        );
         
-        // Add to pot and track bets
-        self.hand.pot = self.small_blind + self.big_blind;
-        self.hand.bets[dealer_index] = self.small_blind;
-        self.hand.bets[non_dealer_index] = self.big_blind;
+        let public_keys = [
+            elgamal::parse_point(&self.elgamal_pks[player_index as usize])?,
+            elgamal::parse_point(&self.elgamal_pks[1 - player_index as usize])?,
+        ];
+        let mut cards = [0u8; 2];
+        for (card, (revealed_card, pocket)) in cards.iter_mut().zip(revealed.cards.iter().zip(pocket_shares)) {
+            require!(
+                self.merkle_hash.digest(&pocket.ciphertext) == revealed_card.ciphertext_digest,
+                PokerError::InvalidMerkleProof
+            );
+            let ciphertext = ElGamalCiphertext::from_bytes(&pocket.ciphertext)?;
+            let shares = [elgamal::parse_point(&pocket.shares[0])?, elgamal::parse_point(&pocket.shares[1])?];
+            for (owner, share) in shares.iter().enumerate() {
+                elgamal::verify_decryption_share(
+                    &public_keys[owner],
+                    &ciphertext,
+                    share,
+                    &PodScalar(pocket.challenges[owner]),
+                    &PodScalar(pocket.responses[owner]),
+                )?;
+            }
+            let plaintext = elgamal::combine_shares(&ciphertext, &shares)?;
+            *card = elgamal::decode_card(&plaintext).ok_or(PokerError::InvalidPocketCards)?;
+        }
+        Ok(cards)
+    }
+    
+    /// Verifying key for a circuit at the version this game pinned
+    /// Version 0 means no key was published: it only verifies against the development key
+    /// on localnet and fails everywhere else. Later versions need their VerifyingKeyAccount.
+    pub fn verifying_key<'a>(
+        &self,
+        circuit: ProofCircuit,
+        account: Option<&'a VerifyingKeyAccount>,
+    ) -> Result<Groth16VerifyingKey<'a>> {
+        let version = self.vk_versions[circuit.index()];
+        if version == 0 {
+            #[cfg(feature = "localnet")]
+            return Ok(circuit.builtin_verifying_key());
+            #[cfg(not(feature = "localnet"))]
+            return err!(PokerError::VerifyingKeyNotPublished);
+        }
+        account
+            .ok_or(PokerError::VerifyingKeyMismatch)?
+            .verifying_key(circuit, version)
+    }
+    
+    /// Check an encrypted card and its Merkle proof against the given deck's commitment
+    pub fn verify_committed_card(&self, deck: DeckKind, card: &CommittedCard) -> Result<()> {
+        let root = self.deck_root(deck);
+        require!(root != [0u8; 32], PokerError::DeckNotCommitted);
+        require!(
+            merkle::verify_inclusion(
+                self.merkle_hash,
+                &root,
+                card.card_index,
+                &card.ciphertext,
+                DECK_SIZE as usize,
+                &card.merkle_proof,
+            ),
+            PokerError::InvalidMerkleProof
+        );
+        Ok(())
+    }
+    
+    /// Check the doubly-encrypted cards backing a reveal, one per deck position in order
+    pub fn verify_revealed_cards(&self, card_indices: &[u8], revealed: &CardMultiproof) -> Result<()> {
+        let root = self.deck_root(DeckKind::Reshuffled);
+        require!(root != [0u8; 32], PokerError::DeckNotCommitted);
+        require!(
+            revealed.cards.len() == card_indices.len()
+                && revealed.cards.iter().zip(card_indices).all(|(card, index)| card.card_index == *index),
+            PokerError::InvalidMerkleProof
+        );
         
+        // Poseidon leaves are None for digests that are not field elements
+        let leaves: Vec<(usize, [u8; 32])> = revealed
+            .cards
+            .iter()
+            .map(|card| {
+                merkle::hash_leaf_digest(self.merkle_hash, card.card_index, &card.ciphertext_digest)
+                    .map(|leaf| (card.card_index as usize, leaf))
+            })
+            .collect::<Option<_>>()
+            .ok_or(PokerError::InvalidMerkleProof)?;
+        require!(
+            merkle::verify_multiproof(
+                self.merkle_hash,
+                &root,
+                &leaves,
+                DECK_SIZE as usize,
+                &revealed.proof,
+            ),
+            PokerError::InvalidMerkleProof
+        );
+        Ok(())
+    }
+    
+    /// Deadline for uploading the given deck, or None if it has not been committed yet
+    pub fn deck_upload_deadline(&self, deck: DeckKind) -> Option<i64> {
+        let proof_type = match deck {
+            DeckKind::Initial => ProofType::DeckCreation,
+            DeckKind::Reshuffled => ProofType::Reshuffle,
+        };
+        self.find_stored_proof(proof_type)
+            .map(|stored| stored.submitted_at + self.action_timeout * DECK_UPLOAD_TIMEOUT_ACTIONS)
+    }
+    
+    /// Post blinds at the start of a hand
+    pub fn post_blinds(&mut self) -> Result<()> {
+        let state = self.engine().post_blinds(&self.betting_state())?;
+        self.apply_betting_state(&state);
         Ok(())
     }
     
     /// Check if betting round is complete
     pub fn is_betting_round_complete(&self) -> bool {
-        // If someone folded, round is complete
-        if self.hand.player_folded[0] || self.hand.player_folded[1] {
-            return true;
+        self.betting_state().is_round_complete()
+    }
+    
+    /// Advance to next betting round; in heads-up, the big blind acts first post-flop
+    pub fn advance_betting_round(&mut self) {
+        let state = self.betting_state().next_street();
+        self.apply_betting_state(&state);
+    }
+    
+    /// Betting rules for this game
+    pub fn engine(&self) -> HandEngine {
+        HandEngine {
+            small_blind: self.small_blind,
+            big_blind: self.big_blind,
+            action_timeout: self.action_timeout,
         }
-        
-        // If both players are all-in, round is complete
-        if self.hand.player_all_in[0] && self.hand.player_all_in[1] {
-            return true;
+    }
+    
+    /// Betting view of the current hand, for the engine
+    pub fn betting_state(&self) -> BettingState {
+        BettingState {
+            street: self.hand.betting_round.into(),
+            open: self.hand.stage.is_betting(),
+            dealer: self.hand.dealer_index,
+            to_act: self.hand.current_turn_index,
+            stacks: self.player_stacks,
+            bets: self.hand.bets,
+            pot: self.hand.pot,
+            last_raise_size: self.hand.last_raise_size,
+            last_aggressor: self.hand.last_aggressor,
+            folded: self.hand.player_folded,
+            all_in: self.hand.player_all_in,
+            has_acted: self.hand.has_acted,
+            winner: self.hand.winner,
+            last_action_at: self.hand.last_action_at,
+            action_deadline: self.hand.action_deadline,
         }
-        
-        // If bets are equal and both players have acted
-        self.hand.bets[0] == self.hand.bets[1]
     }
     
-    /// Advance to next betting round
-    pub fn advance_betting_round(&mut self) {
-        // Move pot forward, reset bets
-        self.hand.bets = [0, 0];
-        
-        // Advance the betting round
-        self.hand.betting_round = match self.hand.betting_round {
-            BettingRound::PreFlop => BettingRound::Flop,
-            BettingRound::Flop => BettingRound::Turn,
-            BettingRound::Turn => BettingRound::River,
-            BettingRound::River => BettingRound::River, // Stay at river
-        };
+    /// Write a state returned by the engine back into the hand; a fold completes
+    /// the hand and an open street moves the hand to its betting stage
+    pub fn apply_betting_state(&mut self, state: &BettingState) {
+        self.hand.betting_round = state.street.into();
+        self.hand.dealer_index = state.dealer;
+        self.hand.current_turn_index = state.to_act;
+        self.player_stacks = state.stacks;
+        self.hand.bets = state.bets;
+        self.hand.pot = state.pot;
+        self.hand.last_raise_size = state.last_raise_size;
+        self.hand.last_aggressor = state.last_aggressor;
+        self.hand.player_folded = state.folded;
+        self.hand.player_all_in = state.all_in;
+        self.hand.has_acted = state.has_acted;
+        self.hand.winner = state.winner;
+        self.hand.last_action_at = state.last_action_at;
+        self.hand.action_deadline = state.action_deadline;
+        self.last_action_timestamp = self.last_action_timestamp.max(state.last_action_at);
         
-        // Update stage
-        self.hand.stage = match self.hand.betting_round {
-            BettingRound::PreFlop => HandStage::PreFlopBetting,
-            BettingRound::Flop => HandStage::FlopBetting,
-            BettingRound::Turn => HandStage::TurnBetting,
-            BettingRound::River => HandStage::RiverBetting,
-        };
+        if state.winner.is_some() {
+            self.hand.stage = HandStage::Complete;
+        } else if state.open {
+            self.hand.stage = HandStage::betting(state.street);
+        }
     }
     
     /// Switch turn to the other player
@@ -295,6 +724,8 @@ impl Game {
         proof: ZkProof,
         clock: &Clock,
     ) -> Result<()> {
+        require!(proof.proof_data.len() <= MAX_PROOF_LEN, GameError::ProofTooLarge);
+        
         let new_proof = StoredProof {
             proof_type,
             submitter_index,
@@ -312,12 +743,69 @@ impl Game {
         err!(GameError::MaxProofsReached)
     }
     
+    /// Find the stored proof of the given type for the current hand
+    pub fn find_stored_proof(&self, proof_type: ProofType) -> Option<&StoredProof> {
+        self.hand.stored_proofs
+            .iter()
+            .flatten()
+            .find(|stored| stored.proof_type == proof_type)
+    }
+    
+    /// Freeze the hand while a disputed proof is checked
+    pub fn enter_dispute(&mut self, challenger_index: u8, disputed_action: DisputedAction) {
+        self.hand.pre_dispute_stage = self.hand.stage;
+        self.hand.pre_dispute_turn_index = self.hand.current_turn_index;
+        self.hand.stage = HandStage::Dispute;
+        self.hand.dispute_active = true;
+        self.hand.challenger_index = challenger_index;
+        self.hand.disputed_action = disputed_action;
+    }
+    
+    /// Close the dispute; the hand resumes where it was unless it has been settled
+    pub fn exit_dispute(&mut self) {
+        if self.hand.stage == HandStage::Dispute {
+            self.hand.stage = self.hand.pre_dispute_stage;
+            self.hand.current_turn_index = self.hand.pre_dispute_turn_index;
+        }
+        self.hand.dispute_active = false;
+    }
+    
+    /// End the hand in favour of `winner_index`: they take the pot and the offender's bond
+    pub fn forfeit_hand(&mut self, winner_index: u8, offender_index: u8) -> Result<()> {
+        require!(offender_index < 2, GameError::InvalidPlayerIndex);
+        self.award_pot(winner_index)?;
+        let bond = self.player_bonds[offender_index as usize];
+        self.player_bonds[offender_index as usize] = 0;
+        self.player_stacks[winner_index as usize] += bond;
+        
+        self.hand.winner = Some(winner_index);
+        self.hand.stage = HandStage::Complete;
+        Ok(())
+    }
+    
+    /// Penalise a frivolous challenger: their bond goes to the accused player
+    pub fn penalise_challenger(&mut self, challenger_index: u8, accused_index: u8) -> Result<()> {
+        require!(
+            challenger_index < 2 && accused_index < 2,
+            GameError::InvalidPlayerIndex
+        );
+        let bond = self.player_bonds[challenger_index as usize];
+        self.player_bonds[challenger_index as usize] = 0;
+        self.player_stacks[accused_index as usize] += bond;
+        Ok(())
+    }
+    
     /// Reveal a community card (store partially decrypted version)
     pub fn reveal_card(
         &mut self,
         card_index: u8,
         partially_decrypted: PartiallyDecryptedCard,
     ) -> Result<()> {
+        require!(
+            partially_decrypted.data.len() <= MAX_CIPHERTEXT_LEN,
+            GameError::CiphertextTooLarge
+        );
+        
         for entry in self.hand.revealed_cards.iter_mut() {
             if entry.is_none() {
                 *entry = Some((card_index, partially_decrypted));
@@ -364,12 +852,51 @@ impl Game {
     }
 }
 
+impl Default for HandState {
+    fn default() -> Self {
+        HandState {
+            stage: HandStage::WaitingForHandCreation,
+            dealer_index: 0,
+            current_turn_index: 0,
+            action_deadline: 0,
+            deck_merkle_root: [0u8; 32],
+            doubly_encrypted_deck_merkle_root: [0u8; 32],
+            pot: 0,
+            bets: [0, 0],
+            betting_round: BettingRound::PreFlop,
+            last_raise_size: 0,
+            last_aggressor: None,
+            revealed_cards: Default::default(),
+            community_cards: [None; 5],
+            pocket_cards: [None; 2],
+            stored_proofs: Default::default(),
+            dispute_active: false,
+            challenger_index: 0,
+            disputed_action: DisputedAction::None,
+            pre_dispute_stage: HandStage::WaitingForHandCreation,
+            pre_dispute_turn_index: 0,
+            player_folded: [false, false],
+            player_all_in: [false, false],
+            has_acted: [false, false],
+            player_revealed_showdown: [false, false],
+            hand_started_at: 0,
+            last_action_at: 0,
+            winner: None,
+            winning_hand_rank: None,
+        }
+    }
+}
+
 impl Default for Game {
     fn default() -> Self {
         Game {
             players: [Pubkey::default(); 2],
-            paillier_pks: [PaillierPublicKey::default(); 2],
+            card_backend: CardBackend::Paillier,
+            paillier_pks: Default::default(),
+            elgamal_pks: [[0u8; 32]; 2],
             player_stacks: [0; 2],
+            player_bonds: [0; 2],
+            game_id: 0,
             current_hand_id: 0,
             game_status: GameStatus::Pending,
             token_vault: Pubkey::default(),
@@ -377,6 +904,9 @@ impl Default for Game {
             small_blind: 0,
             big_blind: 0,
             action_timeout: 0,
+            merkle_hash: MerkleHash::Keccak256,
+            shuffle_verification: ShuffleVerification::Dispute,
+            vk_versions: [0; PROOF_CIRCUIT_COUNT],
             invited_opponent: None,
             hand: HandState::default(),
             bump: 0,
@@ -400,4 +930,400 @@ pub enum GameError {
     MaxProofsReached,
     #[msg("Maximum number of cards have been revealed for this hand")]
     MaxCardsReached,
+    #[msg("Proof exceeds the maximum stored size")]
+    ProofTooLarge,
+    #[msg("Ciphertext exceeds the maximum stored size")]
+    CiphertextTooLarge,
+    #[msg("Card has already been uploaded")]
+    CardAlreadyUploaded,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Action;
+    
+    /// Game whose doubly-encrypted deck holds `[i; 64]` at position i
+    fn game_with_committed_deck(hash: MerkleHash) -> (Game, Vec<[u8; 32]>) {
+        let leaves: Vec<[u8; 32]> = (0..DECK_SIZE).map(|i| merkle::hash_leaf(hash, i, &[i; 64]).unwrap()).collect();
+        let mut game = Game { merkle_hash: hash, ..Game::default() };
+        game.hand.doubly_encrypted_deck_merkle_root = merkle::compute_root(hash, &leaves).unwrap();
+        (game, leaves)
+    }
+    
+    fn multiproof(hash: MerkleHash, leaves: &[[u8; 32]], card_indices: &[u8]) -> CardMultiproof {
+        let positions: Vec<usize> = card_indices.iter().map(|&i| i as usize).collect();
+        CardMultiproof {
+            cards: card_indices
+                .iter()
+                .map(|&i| RevealedCard { card_index: i, ciphertext_digest: merkle::ciphertext_digest(hash, &[i; 64]) })
+                .collect(),
+            proof: merkle::build_multiproof(hash, leaves, &positions).unwrap(),
+        }
+    }
+    
+    #[test]
+    fn test_reveal_requires_committed_cards() {
+        let hash = MerkleHash::Keccak256;
+        let (game, leaves) = game_with_committed_deck(hash);
+        let flop = multiproof(hash, &leaves, &FLOP_CARD_INDICES);
+        assert!(game.verify_revealed_cards(&FLOP_CARD_INDICES, &flop).is_ok());
+        assert!(game
+            .verify_revealed_cards(&DEALER_POCKET_INDICES, &multiproof(hash, &leaves, &DEALER_POCKET_INDICES))
+            .is_ok());
+        
+        // Missing, reordered or substituted cards are rejected
+        let mut missing = flop.clone();
+        missing.cards.pop();
+        assert!(game.verify_revealed_cards(&FLOP_CARD_INDICES, &missing).is_err());
+        let mut reordered = flop.clone();
+        reordered.cards.swap(0, 1);
+        assert!(game.verify_revealed_cards(&FLOP_CARD_INDICES, &reordered).is_err());
+        let mut substituted = multiproof(hash, &leaves, &[TURN_CARD_INDEX]);
+        substituted.cards[0].ciphertext_digest = merkle::ciphertext_digest(hash, &[RIVER_CARD_INDEX; 64]);
+        assert!(game.verify_revealed_cards(&[TURN_CARD_INDEX], &substituted).is_err());
+        
+        // Nothing can be revealed before the deck is committed
+        let uncommitted = Game::default();
+        assert!(uncommitted.verify_revealed_cards(&FLOP_CARD_INDICES, &flop).is_err());
+    }
+    
+    #[test]
+    fn test_runout_reveals_the_rest_of_the_board() {
+        let hash = MerkleHash::Keccak256;
+        let (mut game, leaves) = game_with_committed_deck(hash);
+        assert_eq!(game.undealt_board_indices(), &BOARD_CARD_INDICES);
+        let board = multiproof(hash, &leaves, &BOARD_CARD_INDICES);
+        assert!(game.verify_revealed_cards(game.undealt_board_indices(), &board).is_ok());
+        
+        // After the flop only the turn and the river are left
+        for (position, card) in [12, 25, 38].into_iter().enumerate() {
+            game.finalize_community_card(position, card).unwrap();
+        }
+        assert_eq!(game.undealt_board_indices(), &[TURN_CARD_INDEX, RIVER_CARD_INDEX]);
+        assert!(game.verify_revealed_cards(game.undealt_board_indices(), &board).is_err());
+        let rest = multiproof(hash, &leaves, &[TURN_CARD_INDEX, RIVER_CARD_INDEX]);
+        assert!(game.verify_revealed_cards(game.undealt_board_indices(), &rest).is_ok());
+    }
+    
+    #[test]
+    fn test_card_keys_follow_match_backend() {
+        let elgamal_pk = crate::elgamal::encode_card(9).unwrap().0;
+        let mut game = Game { card_backend: CardBackend::ElGamalRistretto, ..Game::default() };
+        assert!(game.set_card_key(1, CardPublicKey::ElGamal(elgamal_pk)).is_ok());
+        assert_eq!(game.elgamal_pks[1], elgamal_pk);
+        
+        // The joiner can't switch backends, and keys must be valid points
+        let paillier = CardPublicKey::Paillier(PaillierPublicKey::default());
+        assert!(game.set_card_key(0, paillier).is_err());
+        assert!(game.set_card_key(0, CardPublicKey::ElGamal([0xff; 32])).is_err());
+        
+        // ElGamal decks hold 64-byte ciphertexts and 32-byte shares
+        let point = crate::elgamal::RISTRETTO_BASEPOINT.0;
+        let ciphertext = [point, point].concat();
+        assert!(game.validate_deck_ciphertext(DeckKind::Initial, &ciphertext).is_ok());
+        assert!(game.validate_deck_ciphertext(DeckKind::Initial, &point).is_err());
+        assert!(game.validate_decryption_share(&point).is_ok());
+        assert!(game.validate_decryption_share(&ciphertext).is_err());
+    }
+    
+    #[test]
+    fn test_decryption_shares_are_proven() {
+        let scalar = |value: u8| {
+            let mut bytes = [0u8; 32];
+            bytes[0] = value;
+            PodScalar(bytes)
+        };
+        let secrets = [scalar(11), scalar(23)];
+        let mut game = Game { card_backend: CardBackend::ElGamalRistretto, ..Game::default() };
+        for (index, secret) in secrets.iter().enumerate() {
+            let key = CardPublicKey::ElGamal(elgamal::public_key(secret).unwrap().0);
+            game.set_card_key(index as u8, key).unwrap();
+        }
+        
+        // Doubly-encrypted deck with card i at position i
+        let joint = elgamal::joint_key(&game.elgamal_pks).unwrap();
+        let deck: Vec<ElGamalCiphertext> = (0..DECK_SIZE)
+            .map(|i| elgamal::encrypt(&joint, i, &scalar(100 + i)).unwrap())
+            .collect();
+        let leaves: Vec<[u8; 32]> = deck
+            .iter()
+            .enumerate()
+            .map(|(i, card)| merkle::hash_leaf(game.merkle_hash, i as u8, &card.to_bytes()).unwrap())
+            .collect();
+        game.hand.doubly_encrypted_deck_merkle_root = merkle::compute_root(game.merkle_hash, &leaves).unwrap();
+        
+        let flop = CardMultiproof {
+            cards: FLOP_CARD_INDICES
+                .iter()
+                .map(|&i| RevealedCard {
+                    card_index: i,
+                    ciphertext_digest: merkle::ciphertext_digest(game.merkle_hash, &deck[i as usize].to_bytes()),
+                })
+                .collect(),
+            proof: merkle::build_multiproof(game.merkle_hash, &leaves, &[4, 5, 6]).unwrap(),
+        };
+        let prove = |player: usize| -> (Vec<EncryptedCard>, Vec<DecryptionShareProof>) {
+            FLOP_CARD_INDICES
+                .iter()
+                .map(|&i| {
+                    let ciphertext = &deck[i as usize];
+                    let (share, challenge, response) =
+                        elgamal::prove_decryption_share(&secrets[player], ciphertext, &scalar(7 + i)).unwrap();
+                    (
+                        EncryptedCard { data: share.0.to_vec() },
+                        DecryptionShareProof { ciphertext: ciphertext.to_bytes(), challenge: challenge.0, response: response.0 },
+                    )
+                })
+                .unzip()
+        };
+        
+        let (shares, proofs) = prove(1);
+        assert!(game.verify_revealed_cards(&FLOP_CARD_INDICES, &flop).is_ok());
+        assert_eq!(game.verify_decryption_shares(1, &flop, &shares, &proofs).unwrap(), deck[4..7].to_vec());
+        
+        // Shares are checked against the submitting player's key
+        assert!(game.verify_decryption_shares(0, &flop, &shares, &proofs).is_err());
+        
+        // Proofs must be for the revealed ciphertexts, one per card
+        let mut swapped = proofs.clone();
+        swapped.swap(0, 1);
+        assert!(game.verify_decryption_shares(1, &flop, &shares, &swapped).is_err());
+        assert!(game.verify_decryption_shares(1, &flop, &shares, &proofs[..2]).is_err());
+        
+        // Combining both proven shares decrypts the flop
+        for (card_index, share) in FLOP_CARD_INDICES.iter().zip(shares.iter()) {
+            game.reveal_card(*card_index, PartiallyDecryptedCard { data: share.data.clone() }).unwrap();
+        }
+        let (non_dealer_shares, non_dealer_proofs) = prove(0);
+        let ciphertexts = game.verify_decryption_shares(0, &flop, &non_dealer_shares, &non_dealer_proofs).unwrap();
+        assert_eq!(
+            game.decrypt_revealed_cards(&FLOP_CARD_INDICES, &ciphertexts, &non_dealer_shares).unwrap(),
+            FLOP_CARD_INDICES.to_vec()
+        );
+        assert!(game.decrypt_revealed_cards(&FLOP_CARD_INDICES, &ciphertexts, &shares).is_err());
+    }
+    
+    #[test]
+    fn test_pocket_cards_need_both_proven_shares() {
+        let scalar = |value: u8| {
+            let mut bytes = [0u8; 32];
+            bytes[0] = value;
+            PodScalar(bytes)
+        };
+        let secrets = [scalar(11), scalar(23)];
+        let mut game = Game { card_backend: CardBackend::ElGamalRistretto, ..Game::default() };
+        for (index, secret) in secrets.iter().enumerate() {
+            let key = CardPublicKey::ElGamal(elgamal::public_key(secret).unwrap().0);
+            game.set_card_key(index as u8, key).unwrap();
+        }
+        
+        // Doubly-encrypted deck with card i + 10 at position i
+        let joint = elgamal::joint_key(&game.elgamal_pks).unwrap();
+        let deck: Vec<ElGamalCiphertext> = (0..DECK_SIZE)
+            .map(|i| elgamal::encrypt(&joint, (i + 10) % DECK_SIZE, &scalar(100 + i)).unwrap())
+            .collect();
+        let leaves: Vec<[u8; 32]> = deck
+            .iter()
+            .enumerate()
+            .map(|(i, card)| merkle::hash_leaf(game.merkle_hash, i as u8, &card.to_bytes()).unwrap())
+            .collect();
+        game.hand.doubly_encrypted_deck_merkle_root = merkle::compute_root(game.merkle_hash, &leaves).unwrap();
+        
+        let dealer_index = game.hand.dealer_index;
+        let pocket = game.pocket_card_indices(dealer_index);
+        let revealed = CardMultiproof {
+            cards: pocket
+                .iter()
+                .map(|&i| RevealedCard {
+                    card_index: i,
+                    ciphertext_digest: merkle::ciphertext_digest(game.merkle_hash, &deck[i as usize].to_bytes()),
+                })
+                .collect(),
+            proof: merkle::build_multiproof(
+                game.merkle_hash,
+                &leaves,
+                &[pocket[0] as usize, pocket[1] as usize],
+            )
+            .unwrap(),
+        };
+        let pocket_shares: Vec<PocketCardShares> = pocket
+            .iter()
+            .map(|&i| {
+                let ciphertext = &deck[i as usize];
+                let mut shares = PocketCardShares {
+                    ciphertext: ciphertext.to_bytes(),
+                    shares: [[0; 32]; 2],
+                    challenges: [[0; 32]; 2],
+                    responses: [[0; 32]; 2],
+                };
+                // The owner's share first, then the opponent's
+                for (slot, player) in [dealer_index as usize, 1 - dealer_index as usize].into_iter().enumerate() {
+                    let (share, challenge, response) =
+                        elgamal::prove_decryption_share(&secrets[player], ciphertext, &scalar(7 + i + slot as u8)).unwrap();
+                    shares.shares[slot] = share.0;
+                    shares.challenges[slot] = challenge.0;
+                    shares.responses[slot] = response.0;
+                }
+                shares
+            })
+            .collect();
+        
+        let expected = [(pocket[0] + 10) % DECK_SIZE, (pocket[1] + 10) % DECK_SIZE];
+        assert_eq!(game.decrypt_pocket_cards(dealer_index, &revealed, &pocket_shares).unwrap(), expected);
+        
+        // Each share is checked under its own player's key
+        assert!(game.decrypt_pocket_cards(1 - dealer_index, &revealed, &pocket_shares).is_err());
+        
+        // Both pocket cards are needed, for the revealed ciphertexts
+        assert!(game.decrypt_pocket_cards(dealer_index, &revealed, &pocket_shares[..1]).is_err());
+        let swapped = vec![pocket_shares[1].clone(), pocket_shares[0].clone()];
+        assert!(game.decrypt_pocket_cards(dealer_index, &revealed, &swapped).is_err());
+        
+        // A share that is not the opponent's decryption fails its proof
+        let mut forged = pocket_shares.clone();
+        forged[0].shares[1] = forged[0].shares[0];
+        assert!(game.decrypt_pocket_cards(dealer_index, &revealed, &forged).is_err());
+    }
+    
+    #[test]
+    fn test_reveal_uses_game_merkle_hash() {
+        for hash in [MerkleHash::Keccak256, MerkleHash::Sha256, MerkleHash::Poseidon] {
+            let (mut game, leaves) = game_with_committed_deck(hash);
+            let runout = multiproof(hash, &leaves, &[4, 5, 6, 7, 8]);
+            assert!(game.verify_revealed_cards(&[4, 5, 6, 7, 8], &runout).is_ok());
+            
+            let committed = CommittedCard {
+                card_index: 9,
+                ciphertext: vec![9; 64],
+                merkle_proof: merkle::build_proof(hash, &leaves, 9).unwrap(),
+            };
+            game.hand.deck_merkle_root = game.hand.doubly_encrypted_deck_merkle_root;
+            assert!(game.verify_committed_card(DeckKind::Initial, &committed).is_ok());
+            
+            // A deck committed under one hash doesn't verify under another
+            game.merkle_hash = match hash {
+                MerkleHash::Keccak256 => MerkleHash::Sha256,
+                _ => MerkleHash::Keccak256,
+            };
+            assert!(game.verify_revealed_cards(&[4, 5, 6, 7, 8], &runout).is_err());
+            assert!(game.verify_committed_card(DeckKind::Initial, &committed).is_err());
+        }
+    }
+    
+    #[test]
+    fn test_verifying_key_follows_pinned_version() {
+        let published = VerifyingKeyAccount {
+            circuit: ProofCircuit::Reshuffle,
+            version: 2,
+            alpha_g1: [1; 64],
+            beta_g2: [2; 128],
+            gamma_g2: [3; 128],
+            delta_g2: [4; 128],
+            ic: vec![[5; 64]; ProofCircuit::Reshuffle.public_inputs() + 1],
+            bump: 0,
+        };
+        
+        // Unpinned circuits have no key outside localnet, with or without an account
+        let mut game = Game::default();
+        #[cfg(not(feature = "localnet"))]
+        {
+            assert!(game.verifying_key(ProofCircuit::Reshuffle, None).is_err());
+            assert!(game.verifying_key(ProofCircuit::Reshuffle, Some(&published)).is_err());
+        }
+        
+        game.vk_versions[ProofCircuit::Reshuffle.index()] = 2;
+        let vk = game.verifying_key(ProofCircuit::Reshuffle, Some(&published)).unwrap();
+        assert_eq!(vk.alpha_g1, [1; 64]);
+        assert_eq!(vk.ic, &published.ic[..]);
+        
+        // A pinned version needs its own account: not a missing one, another version or another circuit
+        assert!(game.verifying_key(ProofCircuit::Reshuffle, None).is_err());
+        game.vk_versions[ProofCircuit::Reshuffle.index()] = 1;
+        assert!(game.verifying_key(ProofCircuit::Reshuffle, Some(&published)).is_err());
+        game.vk_versions[ProofCircuit::DeckCreation.index()] = 2;
+        assert!(game.verifying_key(ProofCircuit::DeckCreation, Some(&published)).is_err());
+    }
+    
+    #[test]
+    fn test_decryption_dispute_needs_the_accused_decryption() {
+        let mut game = Game::default();
+        let dealer_index = game.hand.dealer_index;
+        let non_dealer_index = game.non_dealer_index();
+        let turn_position = Game::community_card_position(TURN_CARD_INDEX).unwrap();
+        
+        // Nothing is on chain before the dealer's step of the reveal
+        assert!(!game.has_submitted_decryption(dealer_index, TURN_CARD_INDEX));
+        assert!(!game.has_submitted_decryption(non_dealer_index, TURN_CARD_INDEX));
+        
+        // After step 1 only the dealer's share can be disputed
+        game.reveal_card(TURN_CARD_INDEX, PartiallyDecryptedCard { data: vec![7; 64] }).unwrap();
+        assert!(game.has_submitted_decryption(dealer_index, TURN_CARD_INDEX));
+        assert!(!game.has_submitted_decryption(non_dealer_index, TURN_CARD_INDEX));
+        
+        // Step 2 finalizes the card, which is the non-dealer's decryption
+        game.finalize_community_card(turn_position, 30).unwrap();
+        assert!(game.has_submitted_decryption(non_dealer_index, TURN_CARD_INDEX));
+        
+        // Pocket positions never hold a decryption by the non-dealer
+        game.reveal_card(DEALER_POCKET_INDICES[0], PartiallyDecryptedCard { data: vec![8; 64] }).unwrap();
+        assert!(!game.has_submitted_decryption(non_dealer_index, DEALER_POCKET_INDICES[0]));
+    }
+    
+    #[cfg(not(feature = "localnet"))]
+    #[test]
+    fn test_version_zero_verifies_nothing_outside_localnet() {
+        use crate::state::verifying_key::VerifyingKeyRegistry;
+        
+        // A game pinned to version 0 has no key for any circuit
+        let game = Game::default();
+        for circuit in [
+            ProofCircuit::DeckCreation,
+            ProofCircuit::Reshuffle,
+            ProofCircuit::CardDecryption,
+            ProofCircuit::ShowdownReveal,
+        ] {
+            assert!(game.verifying_key(circuit, None).is_err());
+        }
+        
+        // And no game can be created to pin it
+        let mut registry = VerifyingKeyRegistry {
+            admin: Pubkey::default(),
+            current_versions: [0; PROOF_CIRCUIT_COUNT],
+            bump: 0,
+        };
+        assert!(registry.require_published().is_err());
+        registry.current_versions = [1, 1, 0, 1];
+        assert!(registry.require_published().is_err());
+        registry.current_versions = [1, 2, 1, 1];
+        assert!(registry.require_published().is_ok());
+    }
+    
+    #[test]
+    fn test_all_in_hand_accounts_for_every_chip() {
+        let mut game = Game {
+            player_stacks: [100, 10],
+            small_blind: 1,
+            big_blind: 2,
+            action_timeout: 30,
+            ..Game::default()
+        };
+        game.post_blinds().unwrap();
+        game.hand.stage = HandStage::PreFlopBetting;
+        game.hand.current_turn_index = game.hand.dealer_index;
+        
+        for action in [Action::AllIn, Action::Call] {
+            let state = game
+                .engine()
+                .apply(&game.betting_state(), game.hand.current_turn_index, action, 1)
+                .unwrap();
+            game.apply_betting_state(&state);
+        }
+        
+        // The dealer's uncalled 90 chips go back before the showdown
+        assert!(game.is_betting_round_complete());
+        assert_eq!((game.player_stacks, game.hand.bets, game.hand.pot), ([90, 0], [10, 10], 20));
+        game.split_pot();
+        assert_eq!(game.player_stacks, [100, 10]);
+    }
 }
@@ -1,8 +1,16 @@
 pub mod player;
 pub mod game;
 pub mod types;
+pub mod deck_buffer;
+pub mod verification_job;
+pub mod bisection;
+pub mod verifying_key;
 
 pub use player::*;
 pub use game::*;
 pub use types::*;
+pub use deck_buffer::*;
+pub use verification_job::*;
+pub use bisection::*;
+pub use verifying_key::*;
 
@@ -1,8 +1,11 @@
 use anchor_lang::prelude::*;
+use crate::engine::Street;
 
 /// Overall game status (persists across hands)
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum GameStatus {
+    /// Waiting for the second player to join
+    Pending,
     Active,
     Concluded,
 }
@@ -16,10 +19,16 @@ pub enum HandStage {
     WaitingForDealerJoin,
     /// Pre-flop betting in progress
     PreFlopBetting,
+    /// Waiting for both players' decryption shares for the flop
+    AwaitingFlopReveal,
     /// Flop betting in progress
     FlopBetting,
+    /// Waiting for both players' decryption shares for the turn
+    AwaitingTurnReveal,
     /// Turn betting in progress
     TurnBetting,
+    /// Waiting for both players' decryption shares for the river
+    AwaitingRiverReveal,
     /// River betting in progress
     RiverBetting,
     /// Showdown: players revealing pocket cards
@@ -28,6 +37,9 @@ pub enum HandStage {
     Complete,
     /// A dispute has been raised
     Dispute,
+    /// A player is all-in: waiting for both players' decryption shares for every
+    /// board card not dealt yet, after which the hand goes straight to showdown
+    AwaitingRunout,
 }
 
 /// Betting round identifiers
@@ -39,6 +51,49 @@ pub enum BettingRound {
     River,
 }
 
+impl From<BettingRound> for Street {
+    fn from(round: BettingRound) -> Self {
+        match round {
+            BettingRound::PreFlop => Street::PreFlop,
+            BettingRound::Flop => Street::Flop,
+            BettingRound::Turn => Street::Turn,
+            BettingRound::River => Street::River,
+        }
+    }
+}
+
+impl From<Street> for BettingRound {
+    fn from(street: Street) -> Self {
+        match street {
+            Street::PreFlop => BettingRound::PreFlop,
+            Street::Flop => BettingRound::Flop,
+            Street::Turn => BettingRound::Turn,
+            Street::River => BettingRound::River,
+        }
+    }
+}
+
+impl HandStage {
+    /// Stage in which the given street is bet
+    pub fn betting(street: Street) -> Self {
+        match street {
+            Street::PreFlop => HandStage::PreFlopBetting,
+            Street::Flop => HandStage::FlopBetting,
+            Street::Turn => HandStage::TurnBetting,
+            Street::River => HandStage::RiverBetting,
+        }
+    }
+    
+    /// Whether players are betting in this stage
+    pub fn is_betting(&self) -> bool {
+        matches!(
+            self,
+            HandStage::PreFlopBetting | HandStage::FlopBetting |
+            HandStage::TurnBetting | HandStage::RiverBetting
+        )
+    }
+}
+
 /// Player action types
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum PlayerActionType {
@@ -60,10 +115,133 @@ pub enum DisputedAction {
     Reshuffle,
     /// Dispute a card decryption proof (specify card index)
     CardDecryption { card_index: u8 },
-    /// Dispute opponent's showdown reveal
-    ShowdownReveal { player_index: u8 },
+    /// Dispute a card decryption through an interactive bisection of the exponentiation
+    CardDecryptionBisection { card_index: u8 },
+}
+
+/// What the accused's part of a disputed decryption is checked against
+/// Each player strips their own layer with λ, so the last value of the c^λ mod n^2 trace
+/// under the accused's key is fixed by what they posted.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum DecryptionClaim {
+    /// Non-dealer's plaintext m, the last layer: c^λ == 1 + n·(m·λ mod n)
+    Plaintext(Vec<u8>),
+    /// Dealer's share, the λ-layer of the doubly-encrypted card: c^λ == share
+    DealerShare(Vec<u8>),
+}
+
+/// Progress of an on-chain decryption check
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationStatus {
+    InProgress,
+    /// The disputed decryption is correct
+    Valid,
+    /// The disputed decryption is wrong
+    Invalid,
+}
+
+/// The two committed decks of a hand, as uploaded to the DeckBuffer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DeckKind {
+    /// Singly-encrypted deck committed by the non-dealer in create_hand
+    Initial,
+    /// Reshuffled, doubly-encrypted deck committed by the dealer in join_hand
+    Reshuffled,
+}
+
+/// Hash function used for a game's deck Merkle trees
+/// Keccak-256 and SHA-256 run on the runtime's syscalls; Poseidon is the circuit-friendly
+/// choice for decks whose commitments are also checked inside ZK proofs.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MerkleHash {
+    Keccak256,
+    Sha256,
+    /// circomlib Poseidon over the BN254 scalar field
+    Poseidon,
 }
 
+/// Groth16 circuits with separately versioned verifying keys
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ProofCircuit {
+    DeckCreation,
+    Reshuffle,
+    CardDecryption,
+    ShowdownReveal,
+}
+
+impl ProofCircuit {
+    /// Position in per-circuit arrays
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Number of versioned circuits
+pub const PROOF_CIRCUIT_COUNT: usize = 4;
+
+/// Largest number of public inputs of any circuit
+pub const MAX_PUBLIC_INPUTS: usize = 6;
+
+/// When the dealer's reshuffle proof is verified
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ShuffleVerification {
+    /// Stored optimistically in join_hand and only verified if disputed
+    Dispute,
+    /// Verified in join_hand, which fails on an invalid shuffle
+    Immediate,
+}
+
+/// An encrypted card with its Merkle proof against a committed deck
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct CommittedCard {
+    pub card_index: u8,
+    pub ciphertext: Vec<u8>,
+    pub merkle_proof: Vec<[u8; 32]>, // Sibling hashes, bottom-up
+}
+
+/// A revealed deck position and the digest of its doubly-encrypted card
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct RevealedCard {
+    pub card_index: u8,
+    pub ciphertext_digest: [u8; 32], // Ciphertext digest under the game's MerkleHash
+}
+
+/// Several revealed cards with one Merkle multiproof against the doubly-encrypted deck
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct CardMultiproof {
+    pub cards: Vec<RevealedCard>, // In increasing deck order
+    pub proof: Vec<[u8; 32]>, // Shared sibling hashes, level by level
+}
+
+/// Positions of the dealt cards within the doubly-encrypted deck.
+/// The non-dealer's pocket cards are at 0 and 2, the dealer's at 1 and 3,
+/// followed by the flop, turn and river.
+pub const NON_DEALER_POCKET_INDICES: [u8; 2] = [0, 2];
+pub const DEALER_POCKET_INDICES: [u8; 2] = [1, 3];
+pub const FLOP_CARD_INDICES: [u8; 3] = [4, 5, 6];
+pub const TURN_CARD_INDEX: u8 = 7;
+pub const RIVER_CARD_INDEX: u8 = 8;
+pub const BOARD_CARD_INDICES: [u8; 5] = [4, 5, 6, 7, 8];
+
+/// Number of cards in a standard deck
+pub const DECK_SIZE: u8 = 52;
+
+/// Maximum serialized sizes used for account space calculation
+pub const MAX_PAILLIER_N_LEN: usize = 256; // 2048-bit modulus
+pub const MAX_PAILLIER_G_LEN: usize = 257; // g = n + 1
+pub const MAX_CIPHERTEXT_LEN: usize = 512; // ciphertexts live in Z*_{n^2}
+pub const MAX_PROOF_LEN: usize = 256; // uncompressed Groth16 proof (A, B, C)
+
+/// ElGamal sizes: compressed Ristretto255 points, ciphertexts are (c1, c2)
+pub const ELGAMAL_POINT_LEN: usize = 32;
+pub const ELGAMAL_CIPHERTEXT_LEN: usize = 2 * ELGAMAL_POINT_LEN;
+
+/// Exact bit length required of a submitted Paillier modulus
+pub const PAILLIER_N_BITS: usize = MAX_PAILLIER_N_LEN * 8;
+
+/// Number of action timeouts a player gets to upload a committed deck to the DeckBuffer
+pub const DECK_UPLOAD_TIMEOUT_ACTIONS: i64 = 5;
+
 /// Card utilities
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Card(pub u8); // 0-51
@@ -83,11 +261,46 @@ impl Card {
     }
 }
 
-/// Encrypted card representation (for Paillier cryptosystem)
-/// Paillier ciphertext is typically 2048 bits, but we'll use 256 bytes to be safe
+/// Encrypted card representation
+/// Paillier ciphertexts live modulo n^2, so a 2048-bit key gives up to 512 bytes;
+/// ElGamal ciphertexts are 64 bytes
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Default)]
 pub struct EncryptedCard {
-    pub data: Vec<u8>, // Variable-length encrypted value (Paillier ciphertext)
+    pub data: Vec<u8>, // Variable-length encrypted value (Paillier or ElGamal ciphertext)
+}
+
+/// Card encryption scheme used for a match
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CardBackend {
+    /// Commutative Paillier encryption, decryptions are disputed optimistically
+    Paillier,
+    /// ElGamal over Ristretto255 under the players' joint key
+    ElGamalRistretto,
+}
+
+/// A player's card encryption key; the creator's key selects the match's backend
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum CardPublicKey {
+    Paillier(PaillierPublicKey),
+    /// Compressed Ristretto255 point s·G
+    ElGamal([u8; 32]),
+}
+
+impl CardPublicKey {
+    pub fn backend(&self) -> CardBackend {
+        match self {
+            CardPublicKey::Paillier(_) => CardBackend::Paillier,
+            CardPublicKey::ElGamal(_) => CardBackend::ElGamalRistretto,
+        }
+    }
+    
+    /// Check the key is well-formed for its backend
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            CardPublicKey::Paillier(pk) => pk.validate(),
+            CardPublicKey::ElGamal(pk) => crate::elgamal::validate_public_key(pk),
+        }
+    }
 }
 
 /// Paillier public key (n, g where n = p*q for large primes p,q)
@@ -98,6 +311,26 @@ pub struct PaillierPublicKey {
     pub g: Vec<u8>,  // Generator (typically 257 bytes)
 }
 
+/// Chaum–Pedersen proof for an ElGamal decryption share
+/// Carries the card's ciphertext, which must match the digest committed in the deck.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct DecryptionShareProof {
+    pub ciphertext: Vec<u8>, // (c1, c2)
+    pub challenge: [u8; 32],
+    pub response: [u8; 32],
+}
+
+/// Both players' ElGamal decryption shares of one pocket card, revealed at showdown
+/// The owner's share and proof come first, then the opponent's, which was handed over
+/// with its proof when the card was dealt.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct PocketCardShares {
+    pub ciphertext: Vec<u8>, // (c1, c2)
+    pub shares: [[u8; 32]; 2],
+    pub challenges: [[u8; 32]; 2],
+    pub responses: [[u8; 32]; 2],
+}
+
 /// Partially decrypted card (singly decrypted, still encrypted with one player's key)
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Default)]
 pub struct PartiallyDecryptedCard {
@@ -0,0 +1,235 @@
+use anchor_lang::prelude::*;
+use crate::errors::PokerError;
+use crate::modexp;
+use crate::paillier::DecryptionTrace;
+use super::types::*;
+
+/// Resumable on-chain check of a disputed Paillier decryption
+/// Verifies the accused's claim about `ciphertext` under their key: a non-dealer's plaintext
+/// must satisfy L(c^λ mod n^2) == m·λ mod n with g = n + 1, a dealer's share must be
+/// c^λ mod n^2. The exponentiation is spread over many crank_verification transactions,
+/// saving the accumulator after each one.
+#[account]
+pub struct VerificationJob {
+    /// Game, hand and deck position under dispute
+    pub game: Pubkey,
+    pub hand_id: u64,
+    pub card_index: u8,
+    pub accused_index: u8,
+    pub challenger_index: u8,
+    
+    /// Account that paid for the job
+    pub payer: Pubkey,
+    
+    pub status: VerificationStatus,
+    
+    /// Paillier modulus n and the secret λ revealed by the accused
+    pub modulus: Vec<u8>,
+    pub exponent: Vec<u8>,
+    
+    /// Plaintext or share the accused claimed
+    pub claim: DecryptionClaim,
+    
+    /// R^2 mod n^2 hint, checked when the job is opened
+    pub r2: Vec<u8>,
+    
+    /// Ciphertext and running power c^(top bits of λ), both in Montgomery form mod n^2
+    pub base: Vec<u8>,
+    pub accumulator: Vec<u8>,
+    
+    /// Exponent bits still to be processed
+    pub remaining_bits: u16,
+    
+    pub bump: u8,
+}
+
+impl VerificationJob {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // game
+        8 + // hand_id
+        1 + // card_index
+        1 + // accused_index
+        1 + // challenger_index
+        32 + // payer
+        1 + // status
+        (4 + MAX_PAILLIER_N_LEN) + // modulus
+        (4 + MAX_PAILLIER_N_LEN) + // exponent
+        (1 + 4 + MAX_CIPHERTEXT_LEN) + // claim
+        (4 + modexp::WIDE_UINT_BYTES) + // r2
+        (4 + modexp::WIDE_UINT_BYTES) + // base
+        (4 + modexp::WIDE_UINT_BYTES) + // accumulator
+        2 + // remaining_bits
+        1; // bump
+    
+    /// Cost of one 4096-bit Montgomery multiplication mod n^2 on SBF
+    pub const MONTGOMERY_MUL_CU: u32 = 150_000;
+    
+    /// Compute left for the exponentiation out of the 1.4M CU transaction limit, once
+    /// the job account is loaded, re-parsed and written back
+    const CRANK_BUDGET_CU: u32 = 1_400_000 - 200_000;
+    
+    /// Exponent bits processed per crank
+    /// A set bit costs a square and a multiply, so the worst case is two multiplications per bit.
+    pub const BITS_PER_CRANK: u16 = (Self::CRANK_BUDGET_CU / (2 * Self::MONTGOMERY_MUL_CU)) as u16;
+    
+    /// Load the job inputs and prepare the exponentiation
+    /// `lambda_inverse` is μ = λ^-1 mod n, proving that the revealed λ is coprime to n.
+    pub fn start(
+        &mut self,
+        modulus: &[u8],
+        exponent: Vec<u8>,
+        lambda_inverse: &[u8],
+        ciphertext: &[u8],
+        claim: DecryptionClaim,
+        r2: Vec<u8>,
+    ) -> Result<()> {
+        let trace = DecryptionTrace::open(modulus, &exponent, lambda_inverse, &r2)?;
+        
+        self.modulus = modulus.to_vec();
+        self.base = modexp::wide_to_be_bytes(&trace.base(ciphertext)?);
+        self.accumulator = modexp::wide_to_be_bytes(&trace.mont.one());
+        self.remaining_bits = modexp::be_bit_len(&exponent) as u16;
+        self.exponent = exponent;
+        self.r2 = r2;
+        
+        self.status = if trace.final_value(&claim).is_some() {
+            VerificationStatus::InProgress
+        } else {
+            VerificationStatus::Invalid
+        };
+        self.claim = claim;
+        
+        Ok(())
+    }
+    
+    /// Process up to `max_bits` exponent bits and finish the check once all are done
+    pub fn step(&mut self, max_bits: u16) -> Result<()> {
+        require!(
+            self.status == VerificationStatus::InProgress,
+            PokerError::VerificationComplete
+        );
+        
+        let trace = DecryptionTrace::new(&self.modulus, &self.exponent, &self.r2)?;
+        let base = modexp::wide_from_be_bytes(&self.base).ok_or(PokerError::InvalidVerificationJob)?;
+        let mut acc = modexp::wide_from_be_bytes(&self.accumulator).ok_or(PokerError::InvalidVerificationJob)?;
+        
+        let steps = max_bits.min(self.remaining_bits);
+        for _ in 0..steps {
+            self.remaining_bits -= 1;
+            let bit = modexp::be_bit(&self.exponent, self.remaining_bits as usize);
+            acc = trace.mont.pow_step(&acc, &base, bit);
+        }
+        self.accumulator = modexp::wide_to_be_bytes(&acc);
+        
+        if self.remaining_bits == 0 {
+            let power = trace.mont.from_montgomery(&acc);
+            self.status = if trace.final_value(&self.claim) == Some(power) {
+                VerificationStatus::Valid
+            } else {
+                VerificationStatus::Invalid
+            };
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{encrypt, n_squared, pow, r2_hint, LAMBDA, LAMBDA_INVERSE, N};
+    
+    fn new_job() -> VerificationJob {
+        VerificationJob {
+            game: Pubkey::default(),
+            hand_id: 1,
+            card_index: 4,
+            accused_index: 0,
+            challenger_index: 1,
+            payer: Pubkey::default(),
+            status: VerificationStatus::InProgress,
+            modulus: vec![],
+            exponent: vec![],
+            claim: DecryptionClaim::Plaintext(vec![]),
+            r2: vec![],
+            base: vec![],
+            accumulator: vec![],
+            remaining_bits: 0,
+            bump: 0,
+        }
+    }
+    
+    fn r2() -> Vec<u8> {
+        modexp::wide_to_be_bytes(&r2_hint(&n_squared()))
+    }
+    
+    fn run(ciphertext: &[u8], claim: DecryptionClaim) -> Result<VerificationStatus> {
+        let mut job = new_job();
+        job.start(&N.to_be_bytes(), LAMBDA.to_be_bytes().to_vec(), &LAMBDA_INVERSE.to_be_bytes(), ciphertext, claim, r2())?;
+        
+        // Crank one bit at a time to exercise the saved intermediate state
+        let mut cranks = 0;
+        while job.status == VerificationStatus::InProgress {
+            job.step(1)?;
+            cranks += 1;
+        }
+        assert!(cranks == 0 || cranks == modexp::be_bit_len(&LAMBDA.to_be_bytes()));
+        assert!(job.step(1).is_err());
+        Ok(job.status)
+    }
+    
+    #[test]
+    fn test_correct_decryption_is_valid() {
+        let ciphertext = modexp::wide_to_be_bytes(&encrypt(42, 17));
+        assert!(run(&ciphertext, DecryptionClaim::Plaintext(vec![42])).unwrap() == VerificationStatus::Valid);
+    }
+    
+    #[test]
+    fn test_wrong_plaintext_is_invalid() {
+        let ciphertext = modexp::wide_to_be_bytes(&encrypt(42, 17));
+        assert!(run(&ciphertext, DecryptionClaim::Plaintext(vec![43])).unwrap() == VerificationStatus::Invalid);
+        
+        // Plaintexts outside Z_n fail without cranking
+        let out_of_range = DecryptionClaim::Plaintext((N + 42).to_be_bytes().to_vec());
+        assert!(run(&ciphertext, out_of_range).unwrap() == VerificationStatus::Invalid);
+    }
+    
+    #[test]
+    fn test_honest_dealer_share_is_valid() {
+        // The dealer's share is the full-width λ-layer of the card, not a value in Z_n
+        let card = encrypt(42, 17);
+        let share = modexp::wide_to_be_bytes(&pow(&card, LAMBDA));
+        assert_eq!(share.len(), modexp::WIDE_UINT_BYTES);
+        let ciphertext = modexp::wide_to_be_bytes(&card);
+        assert!(run(&ciphertext, DecryptionClaim::DealerShare(share)).unwrap() == VerificationStatus::Valid);
+        
+        // Any other share is caught, one outside Z_{n^2} without cranking
+        let forged = modexp::wide_to_be_bytes(&pow(&encrypt(43, 17), LAMBDA));
+        assert!(run(&ciphertext, DecryptionClaim::DealerShare(forged)).unwrap() == VerificationStatus::Invalid);
+        let out_of_range = modexp::wide_to_be_bytes(&n_squared());
+        assert!(run(&ciphertext, DecryptionClaim::DealerShare(out_of_range)).unwrap() == VerificationStatus::Invalid);
+    }
+    
+    #[test]
+    fn test_bad_inputs_rejected() {
+        let ciphertext = modexp::wide_to_be_bytes(&encrypt(42, 17));
+        let claim = || DecryptionClaim::Plaintext(vec![42]);
+        let lambda = LAMBDA.to_be_bytes().to_vec();
+        let mu = LAMBDA_INVERSE.to_be_bytes();
+        let mut job = new_job();
+        
+        // Wrong R^2 hint
+        assert!(job.start(&N.to_be_bytes(), lambda.clone(), &mu, &ciphertext, claim(), vec![1]).is_err());
+        
+        // λ must be in (0, n)
+        assert!(job.start(&N.to_be_bytes(), vec![0], &mu, &ciphertext, claim(), r2()).is_err());
+        assert!(job.start(&N.to_be_bytes(), N.to_be_bytes().to_vec(), &mu, &ciphertext, claim(), r2()).is_err());
+        
+        // and invertible mod n, shown by μ = λ^-1 mod n
+        assert!(job.start(&N.to_be_bytes(), lambda.clone(), &[1], &ciphertext, claim(), r2()).is_err());
+        // λ = 61 shares a factor with n, so no μ works
+        for mu in [1, 53, LAMBDA_INVERSE, N - 1] {
+            assert!(job.start(&N.to_be_bytes(), vec![61], &mu.to_be_bytes(), &ciphertext, claim(), r2()).is_err());
+        }
+        assert!(job.start(&N.to_be_bytes(), lambda, &mu, &ciphertext, claim(), r2()).is_ok());
+    }
+}
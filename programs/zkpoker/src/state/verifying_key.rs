@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use crate::errors::PokerError;
+use crate::zk::Groth16VerifyingKey;
+use super::types::*;
+
+/// Admin-controlled registry of the current Groth16 verifying key version per circuit
+/// Keys are published as separate VerifyingKeyAccount PDAs, so older versions stay
+/// available to the games that pinned them.
+#[account]
+pub struct VerifyingKeyRegistry {
+    /// Account allowed to publish keys and hand over the registry
+    pub admin: Pubkey,
+    
+    /// Latest published version per circuit, indexed by `ProofCircuit::index`
+    /// Version 0 means nothing was published yet; only localnet builds accept it.
+    pub current_versions: [u32; PROOF_CIRCUIT_COUNT],
+    
+    pub bump: u8,
+}
+
+impl VerifyingKeyRegistry {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // admin
+        4 * PROOF_CIRCUIT_COUNT + // current_versions
+        1; // bump
+    
+    /// Check every circuit has a published key a new game can pin
+    /// Localnet builds may pin version 0, the development keys.
+    pub fn require_published(&self) -> Result<()> {
+        if cfg!(feature = "localnet") {
+            return Ok(());
+        }
+        require!(
+            self.current_versions.iter().all(|&version| version > 0),
+            PokerError::VerifyingKeyNotPublished
+        );
+        Ok(())
+    }
+}
+
+/// One published version of a circuit's Groth16 verifying key
+#[account]
+pub struct VerifyingKeyAccount {
+    pub circuit: ProofCircuit,
+    pub version: u32,
+    
+    /// Key in the alt_bn128 syscall encoding
+    pub alpha_g1: [u8; 64],
+    pub beta_g2: [u8; 128],
+    pub gamma_g2: [u8; 128],
+    pub delta_g2: [u8; 128],
+    pub ic: Vec<[u8; 64]>, // IC[0] plus one point per public input
+    
+    pub bump: u8,
+}
+
+impl VerifyingKeyAccount {
+    pub const LEN: usize = 8 + // discriminator
+        1 + // circuit
+        4 + // version
+        64 + // alpha_g1
+        128 * 3 + // beta_g2, gamma_g2, delta_g2
+        (4 + 64 * (MAX_PUBLIC_INPUTS + 1)) + // ic
+        1; // bump
+    
+    /// Borrow as a verifying key, checking it is the expected circuit and version
+    pub fn verifying_key(&self, circuit: ProofCircuit, version: u32) -> Result<Groth16VerifyingKey<'_>> {
+        require!(
+            self.circuit == circuit && self.version == version,
+            PokerError::VerifyingKeyMismatch
+        );
+        Ok(Groth16VerifyingKey {
+            alpha_g1: self.alpha_g1,
+            beta_g2: self.beta_g2,
+            gamma_g2: self.gamma_g2,
+            delta_g2: self.delta_g2,
+            ic: &self.ic,
+        })
+    }
+}
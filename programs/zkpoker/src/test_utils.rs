@@ -0,0 +1,42 @@
+use crypto_bigint::modular::runtime_mod::{DynResidue, DynResidueParams};
+use crate::modexp::WideUint;
+use crate::state::PaillierPublicKey;
+
+// Fixtures shared by the Paillier and modular arithmetic tests
+
+// Toy key: n = 61 * 53, g = n + 1, λ = lcm(60, 52) = 780, μ = λ^-1 mod n
+pub(crate) const N: u64 = 3233;
+pub(crate) const LAMBDA: u64 = 780;
+pub(crate) const LAMBDA_INVERSE: u64 = 1173;
+
+pub(crate) fn toy_key() -> PaillierPublicKey {
+    PaillierPublicKey {
+        n: N.to_be_bytes().to_vec(),
+        g: (N + 1).to_be_bytes().to_vec(),
+    }
+}
+
+pub(crate) fn n_squared() -> WideUint {
+    let n = WideUint::from_u64(N);
+    n.wrapping_mul(&n)
+}
+
+/// base^exponent mod n^2
+pub(crate) fn pow(base: &WideUint, exponent: u64) -> WideUint {
+    let params = DynResidueParams::new(&n_squared());
+    DynResidue::new(base, params).pow(&WideUint::from_u64(exponent)).retrieve()
+}
+
+/// (1 + n)^m · r^n mod n^2
+pub(crate) fn encrypt(m: u64, r: u64) -> WideUint {
+    let params = DynResidueParams::new(&n_squared());
+    let g_m = DynResidue::new(&WideUint::from_u64(1 + m * N), params);
+    let r_n = DynResidue::new(&pow(&WideUint::from_u64(r), N), params);
+    g_m.mul(&r_n).retrieve()
+}
+
+/// R^2 mod modulus, computed the slow way (host only)
+pub(crate) fn r2_hint(modulus: &WideUint) -> WideUint {
+    let r = WideUint::MAX.const_rem(modulus).0.wrapping_add(&WideUint::ONE);
+    WideUint::const_rem_wide(r.square_wide(), modulus).0
+}
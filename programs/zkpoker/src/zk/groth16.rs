@@ -0,0 +1,341 @@
+use anchor_lang::prelude::*;
+#[cfg(not(feature = "mock-zk"))]
+use anchor_lang::solana_program::alt_bn128::prelude::{
+    alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing,
+};
+use crate::errors::PokerError;
+
+#[cfg(all(feature = "mock-zk", not(feature = "localnet")))]
+compile_error!("the mock-zk feature accepts forged proofs and can only be built with the localnet feature");
+
+// Groth16 verification over BN254
+//
+// Uses `solana_program::alt_bn128`, which calls the alt_bn128 syscalls when built for
+// SBF and falls back to a pure-Rust arkworks implementation on the host, so the same
+// code path runs on-chain and in `cargo test`.
+//
+// Encoding (big-endian, EIP-197):
+// - G1 points are `x || y` (64 bytes)
+// - G2 points are `x_c1 || x_c0 || y_c1 || y_c0` (128 bytes)
+// - Proofs are `-A || B || C` (256 bytes); A is negated by the prover so the
+//   verifier can check a single product of pairings
+// - Public inputs are 32-byte scalars that must be below the BN254 scalar field modulus
+
+/// Serialized proof length: -A (G1) + B (G2) + C (G1)
+pub const GROTH16_PROOF_LEN: usize = 64 + 128 + 64;
+
+/// Leading bytes of a proof accepted by the `mock-zk` verifier
+pub const MOCK_PROOF_PREFIX: &[u8] = b"zkpoker:mock-groth16";
+
+/// BN254 scalar field modulus r, big-endian
+pub const BN254_SCALAR_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// Groth16 verifying key in the alt_bn128 syscall encoding
+pub struct Groth16VerifyingKey<'a> {
+    pub alpha_g1: [u8; 64],
+    pub beta_g2: [u8; 128],
+    pub gamma_g2: [u8; 128],
+    pub delta_g2: [u8; 128],
+    /// IC[0] plus one point per public input
+    pub ic: &'a [[u8; 64]],
+}
+
+/// Groth16 proof with A already negated
+pub struct Groth16Proof {
+    pub a_neg: [u8; 64],
+    pub b: [u8; 128],
+    pub c: [u8; 64],
+}
+
+impl Groth16Proof {
+    /// Parse the `-A || B || C` encoding stored in `ZkProof::proof_data`
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        require!(data.len() == GROTH16_PROOF_LEN, PokerError::InvalidProofData);
+        
+        let mut proof = Groth16Proof {
+            a_neg: [0u8; 64],
+            b: [0u8; 128],
+            c: [0u8; 64],
+        };
+        proof.a_neg.copy_from_slice(&data[..64]);
+        proof.b.copy_from_slice(&data[64..192]);
+        proof.c.copy_from_slice(&data[192..]);
+        Ok(proof)
+    }
+}
+
+/// Verify a Groth16 proof against a verifying key and public inputs
+pub fn verify_groth16(
+    vk: &Groth16VerifyingKey,
+    proof: &Groth16Proof,
+    public_inputs: &[[u8; 32]],
+) -> Result<()> {
+    require!(
+        vk.ic.len() == public_inputs.len() + 1,
+        PokerError::InvalidPublicInputs
+    );
+    require!(
+        public_inputs.iter().all(is_scalar_in_field),
+        PokerError::InvalidPublicInputs
+    );
+    
+    check_pairing(vk, proof, public_inputs)
+}
+
+/// Deterministic stand-in for local testing: accepts exactly the proofs whose -A
+/// starts with `MOCK_PROOF_PREFIX`
+#[cfg(feature = "mock-zk")]
+fn check_pairing(
+    _vk: &Groth16VerifyingKey,
+    proof: &Groth16Proof,
+    _public_inputs: &[[u8; 32]],
+) -> Result<()> {
+    require!(
+        proof.a_neg.starts_with(MOCK_PROOF_PREFIX),
+        PokerError::ProofVerificationFailed
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "mock-zk"))]
+fn check_pairing(
+    vk: &Groth16VerifyingKey,
+    proof: &Groth16Proof,
+    public_inputs: &[[u8; 32]],
+) -> Result<()> {
+    // vk_x = IC[0] + sum(input_i * IC[i + 1])
+    let mut vk_x = vk.ic[0];
+    for (input, ic_point) in public_inputs.iter().zip(vk.ic[1..].iter()) {
+        let mut mul_input = [0u8; 96];
+        mul_input[..64].copy_from_slice(ic_point);
+        mul_input[64..].copy_from_slice(input);
+        let product = alt_bn128_multiplication(&mul_input)
+            .map_err(|_| error!(PokerError::InvalidProofData))?;
+        
+        let mut add_input = [0u8; 128];
+        add_input[..64].copy_from_slice(&vk_x);
+        add_input[64..].copy_from_slice(&product);
+        let sum = alt_bn128_addition(&add_input)
+            .map_err(|_| error!(PokerError::InvalidProofData))?;
+        vk_x.copy_from_slice(&sum);
+    }
+    
+    // e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1
+    let mut pairing_input = Vec::with_capacity(4 * 192);
+    pairing_input.extend_from_slice(&proof.a_neg);
+    pairing_input.extend_from_slice(&proof.b);
+    pairing_input.extend_from_slice(&vk.alpha_g1);
+    pairing_input.extend_from_slice(&vk.beta_g2);
+    pairing_input.extend_from_slice(&vk_x);
+    pairing_input.extend_from_slice(&vk.gamma_g2);
+    pairing_input.extend_from_slice(&proof.c);
+    pairing_input.extend_from_slice(&vk.delta_g2);
+    
+    let result = alt_bn128_pairing(&pairing_input)
+        .map_err(|_| error!(PokerError::InvalidProofData))?;
+    require!(
+        result.len() == 32 && result[..31].iter().all(|b| *b == 0) && result[31] == 1,
+        PokerError::ProofVerificationFailed
+    );
+    
+    Ok(())
+}
+
+/// Check that a big-endian scalar is strictly below the BN254 scalar field modulus
+pub fn is_scalar_in_field(scalar: &[u8; 32]) -> bool {
+    scalar < &BN254_SCALAR_MODULUS
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use ark_bn254::{Bn254, Fr, G1Affine, G2Affine};
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_groth16::Groth16;
+    use ark_relations::lc;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+    use ark_snark::SNARK;
+    use ark_std::rand::SeedableRng;
+    
+    /// Proves knowledge of x such that x * x * public_a = public_b + public_c + public_d
+    #[derive(Clone)]
+    pub(crate) struct TestCircuit {
+        pub x: Option<Fr>,
+        pub inputs: [Option<Fr>; 4],
+    }
+    
+    impl ConstraintSynthesizer<Fr> for TestCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> core::result::Result<(), SynthesisError> {
+            let x = cs.new_witness_variable(|| self.x.ok_or(SynthesisError::AssignmentMissing))?;
+            let inputs = self
+                .inputs
+                .map(|v| cs.new_input_variable(|| v.ok_or(SynthesisError::AssignmentMissing)));
+            let [a, b, c, d] = [inputs[0]?, inputs[1]?, inputs[2]?, inputs[3]?];
+            
+            let x_squared = cs.new_witness_variable(|| {
+                let x = self.x.ok_or(SynthesisError::AssignmentMissing)?;
+                Ok(x * x)
+            })?;
+            cs.enforce_constraint(lc!() + x, lc!() + x, lc!() + x_squared)?;
+            cs.enforce_constraint(lc!() + x_squared, lc!() + a, lc!() + b + c + d)?;
+            Ok(())
+        }
+    }
+    
+    pub(crate) fn g1_bytes(point: &G1Affine) -> [u8; 64] {
+        let mut out = [0u8; 64];
+        out[..32].copy_from_slice(&point.x.into_bigint().to_bytes_be());
+        out[32..].copy_from_slice(&point.y.into_bigint().to_bytes_be());
+        out
+    }
+    
+    pub(crate) fn g2_bytes(point: &G2Affine) -> [u8; 128] {
+        let mut out = [0u8; 128];
+        out[..32].copy_from_slice(&point.x.c1.into_bigint().to_bytes_be());
+        out[32..64].copy_from_slice(&point.x.c0.into_bigint().to_bytes_be());
+        out[64..96].copy_from_slice(&point.y.c1.into_bigint().to_bytes_be());
+        out[96..].copy_from_slice(&point.y.c0.into_bigint().to_bytes_be());
+        out
+    }
+    
+    pub(crate) fn scalar_bytes(value: &Fr) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        out.copy_from_slice(&value.into_bigint().to_bytes_be());
+        out
+    }
+    
+    /// Convert scalars from the syscall encoding back into field elements
+    pub(crate) fn scalar_from_bytes(bytes: &[u8; 32]) -> Fr {
+        Fr::from_be_bytes_mod_order(bytes)
+    }
+    
+    /// Owned verifying key produced by the test setup
+    pub(crate) struct TestVerifyingKey {
+        pub alpha_g1: [u8; 64],
+        pub beta_g2: [u8; 128],
+        pub gamma_g2: [u8; 128],
+        pub delta_g2: [u8; 128],
+        pub ic: Vec<[u8; 64]>,
+    }
+    
+    impl TestVerifyingKey {
+        pub fn as_vk(&self) -> Groth16VerifyingKey<'_> {
+            Groth16VerifyingKey {
+                alpha_g1: self.alpha_g1,
+                beta_g2: self.beta_g2,
+                gamma_g2: self.gamma_g2,
+                delta_g2: self.delta_g2,
+                ic: &self.ic,
+            }
+        }
+    }
+    
+    /// Run a deterministic setup and prove the test circuit for the given public inputs
+    pub(crate) fn prove_test_circuit(public_inputs: [Fr; 4]) -> (TestVerifyingKey, Vec<u8>) {
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(42);
+        let setup = TestCircuit { x: None, inputs: [None; 4] };
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(setup, &mut rng).unwrap();
+        
+        // Choose a = 1 and b + c + d = x^2 by adjusting d
+        let x = Fr::from(7u64);
+        let [a, b, c, _] = public_inputs;
+        let d = x * x * a - b - c;
+        let circuit = TestCircuit { x: Some(x), inputs: [Some(a), Some(b), Some(c), Some(d)] };
+        let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut rng).unwrap();
+        
+        let mut proof_data = Vec::with_capacity(GROTH16_PROOF_LEN);
+        proof_data.extend_from_slice(&g1_bytes(&(-proof.a)));
+        proof_data.extend_from_slice(&g2_bytes(&proof.b));
+        proof_data.extend_from_slice(&g1_bytes(&proof.c));
+        
+        let test_vk = TestVerifyingKey {
+            alpha_g1: g1_bytes(&vk.alpha_g1),
+            beta_g2: g2_bytes(&vk.beta_g2),
+            gamma_g2: g2_bytes(&vk.gamma_g2),
+            delta_g2: g2_bytes(&vk.delta_g2),
+            ic: vk.gamma_abc_g1.iter().map(g1_bytes).collect(),
+        };
+        (test_vk, proof_data)
+    }
+    
+    fn fixture() -> (TestVerifyingKey, Vec<u8>, [[u8; 32]; 4]) {
+        let (a, b, c) = (Fr::from(3u64), Fr::from(5u64), Fr::from(11u64));
+        let d = Fr::from(49u64) * a - b - c;
+        let (vk, proof) = prove_test_circuit([a, b, c, d]);
+        let inputs = [scalar_bytes(&a), scalar_bytes(&b), scalar_bytes(&c), scalar_bytes(&d)];
+        (vk, proof, inputs)
+    }
+    
+    #[cfg(not(feature = "mock-zk"))]
+    #[test]
+    fn test_valid_proof_verifies() {
+        let (test_vk, proof, inputs) = fixture();
+        let vk = test_vk.as_vk();
+        let proof = Groth16Proof::from_bytes(&proof).unwrap();
+        assert!(verify_groth16(&vk, &proof, &inputs).is_ok());
+    }
+    
+    #[test]
+    fn test_wrong_public_input_rejected() {
+        let (test_vk, proof, mut inputs) = fixture();
+        let vk = test_vk.as_vk();
+        let proof = Groth16Proof::from_bytes(&proof).unwrap();
+        inputs[1][31] ^= 1;
+        assert!(verify_groth16(&vk, &proof, &inputs).is_err());
+    }
+    
+    #[test]
+    fn test_tampered_proof_rejected() {
+        let (test_vk, proof, inputs) = fixture();
+        let vk = test_vk.as_vk();
+        
+        // Swapping -A for A must fail the pairing check
+        let mut tampered = proof.clone();
+        let a = G1Affine::new(
+            ark_bn254::Fq::from_be_bytes_mod_order(&proof[..32]),
+            ark_bn254::Fq::from_be_bytes_mod_order(&proof[32..64]),
+        );
+        tampered[..64].copy_from_slice(&g1_bytes(&(-a)));
+        let tampered = Groth16Proof::from_bytes(&tampered).unwrap();
+        assert!(verify_groth16(&vk, &tampered, &inputs).is_err());
+        
+        assert!(Groth16Proof::from_bytes(&proof[..255]).is_err());
+    }
+    
+    #[test]
+    fn test_public_input_must_be_in_field() {
+        let (test_vk, proof, mut inputs) = fixture();
+        let vk = test_vk.as_vk();
+        let proof = Groth16Proof::from_bytes(&proof).unwrap();
+        
+        // input + r is the same field element but must not be accepted
+        assert!(!is_scalar_in_field(&BN254_SCALAR_MODULUS));
+        inputs[0] = BN254_SCALAR_MODULUS;
+        inputs[0][31] += 3;
+        assert_eq!(scalar_from_bytes(&inputs[0]), Fr::from(3u64));
+        assert!(verify_groth16(&vk, &proof, &inputs).is_err());
+        
+        // Wrong number of inputs
+        assert!(verify_groth16(&vk, &proof, &inputs[..3]).is_err());
+    }
+    
+    #[cfg(feature = "mock-zk")]
+    #[test]
+    fn test_mock_verifier_checks_prefix() {
+        let (test_vk, _, inputs) = fixture();
+        let vk = test_vk.as_vk();
+        
+        let mut mock = [0u8; GROTH16_PROOF_LEN];
+        mock[..MOCK_PROOF_PREFIX.len()].copy_from_slice(MOCK_PROOF_PREFIX);
+        let proof = Groth16Proof::from_bytes(&mock).unwrap();
+        assert!(verify_groth16(&vk, &proof, &inputs).is_ok());
+        assert!(verify_groth16(&vk, &proof, &inputs[..3]).is_err());
+        
+        mock[0] ^= 1;
+        let proof = Groth16Proof::from_bytes(&mock).unwrap();
+        assert!(verify_groth16(&vk, &proof, &inputs).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+pub mod groth16;
+pub mod verifying_keys;
+
+pub use groth16::*;
+pub use verifying_keys::*;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::state::*;
+use crate::errors::PokerError;
+
+/// Split a 32-byte commitment into two field elements (high and low 128 bits)
+/// so it can be used as a public input without reduction mod r
+pub fn commitment_to_field_elements(commitment: &[u8; 32]) -> [[u8; 32]; 2] {
+    let mut hi = [0u8; 32];
+    let mut lo = [0u8; 32];
+    hi[16..].copy_from_slice(&commitment[..16]);
+    lo[16..].copy_from_slice(&commitment[16..]);
+    [hi, lo]
+}
+
+/// Hash a Paillier public key into a single field element
+/// The key components are length-prefixed before hashing and the top byte of the
+/// digest is cleared, so the result is always below the BN254 scalar modulus
+pub fn paillier_key_to_field_element(pk: &PaillierPublicKey) -> [u8; 32] {
+    let n_len = (pk.n.len() as u32).to_le_bytes();
+    let g_len = (pk.g.len() as u32).to_le_bytes();
+    let mut digest = hashv(&[b"zkpoker:paillier_pk", &n_len, &pk.n, &g_len, &pk.g]).to_bytes();
+    digest[0] = 0;
+    digest
+}
+
+/// Hash an ElGamal public key into a single field element
+pub fn elgamal_key_to_field_element(pk: &[u8; 32]) -> [u8; 32] {
+    let mut digest = hashv(&[b"zkpoker:elgamal_pk", pk]).to_bytes();
+    digest[0] = 0;
+    digest
+}
+
+/// Both players' card keys as field elements, for the match's backend
+pub fn card_keys_to_field_elements(game: &Game) -> [[u8; 32]; 2] {
+    match game.card_backend {
+        CardBackend::Paillier => [
+            paillier_key_to_field_element(&game.paillier_pks[0]),
+            paillier_key_to_field_element(&game.paillier_pks[1]),
+        ],
+        CardBackend::ElGamalRistretto => [
+            elgamal_key_to_field_element(&game.elgamal_pks[0]),
+            elgamal_key_to_field_element(&game.elgamal_pks[1]),
+        ],
+    }
+}
+
+/// Public inputs of ProveCorrectDeckCreation:
+/// [root_hi, root_lo, H(card_pks[0]), H(card_pks[1])]
+pub fn deck_creation_public_inputs(
+    deck_merkle_root: &[u8; 32],
+    card_keys: &[[u8; 32]; 2],
+) -> [[u8; 32]; DECK_CREATION_PUBLIC_INPUTS] {
+    let [root_hi, root_lo] = commitment_to_field_elements(deck_merkle_root);
+    [root_hi, root_lo, card_keys[0], card_keys[1]]
+}
+
+/// Verify a deck creation proof against the committed deck and both players' keys
+pub fn verify_deck_creation_proof(
+    vk: &Groth16VerifyingKey,
+    proof: &ZkProof,
+    deck_merkle_root: &[u8; 32],
+    card_keys: &[[u8; 32]; 2],
+) -> Result<()> {
+    let public_inputs = deck_creation_public_inputs(deck_merkle_root, card_keys);
+    let proof = Groth16Proof::from_bytes(&proof.proof_data)?;
+    verify_groth16(vk, &proof, &public_inputs)
+}
+
+/// Public inputs of ProveCorrectReshuffle:
+/// [deck_hi, deck_lo, doubly_hi, doubly_lo, H(card_pks[0]), H(card_pks[1])]
+pub fn reshuffle_public_inputs(
+    deck_merkle_root: &[u8; 32],
+    doubly_encrypted_deck_merkle_root: &[u8; 32],
+    card_keys: &[[u8; 32]; 2],
+) -> [[u8; 32]; RESHUFFLE_PUBLIC_INPUTS] {
+    let [deck_hi, deck_lo] = commitment_to_field_elements(deck_merkle_root);
+    let [doubly_hi, doubly_lo] = commitment_to_field_elements(doubly_encrypted_deck_merkle_root);
+    [deck_hi, deck_lo, doubly_hi, doubly_lo, card_keys[0], card_keys[1]]
+}
+
+/// Verify a reshuffle proof: the doubly-encrypted deck is a permutation of the committed
+/// deck, re-encrypted by the dealer
+pub fn verify_reshuffle_proof(
+    vk: &Groth16VerifyingKey,
+    proof: &ZkProof,
+    deck_merkle_root: &[u8; 32],
+    doubly_encrypted_deck_merkle_root: &[u8; 32],
+    card_keys: &[[u8; 32]; 2],
+) -> Result<()> {
+    let public_inputs =
+        reshuffle_public_inputs(deck_merkle_root, doubly_encrypted_deck_merkle_root, card_keys);
+    let proof = Groth16Proof::from_bytes(&proof.proof_data)?;
+    verify_groth16(vk, &proof, &public_inputs)
+}
+
+/// Public inputs of ProveCorrectShowdownReveal:
+/// [doubly_hi, doubly_lo, card_0, card_1, H(player key)]
+pub fn showdown_reveal_public_inputs(
+    doubly_encrypted_deck_merkle_root: &[u8; 32],
+    pocket_cards: &[u8; 2],
+    player_key: &[u8; 32],
+) -> [[u8; 32]; SHOWDOWN_REVEAL_PUBLIC_INPUTS] {
+    let [doubly_hi, doubly_lo] = commitment_to_field_elements(doubly_encrypted_deck_merkle_root);
+    [
+        doubly_hi,
+        doubly_lo,
+        u8_to_field_element(pocket_cards[0]),
+        u8_to_field_element(pocket_cards[1]),
+        *player_key,
+    ]
+}
+
+/// Verify a showdown proof: the player's pocket positions of the doubly-encrypted deck
+/// hold the revealed cards
+pub fn verify_showdown_reveal_proof(
+    vk: &Groth16VerifyingKey,
+    proof: &ZkProof,
+    doubly_encrypted_deck_merkle_root: &[u8; 32],
+    pocket_cards: &[u8; 2],
+    player_key: &[u8; 32],
+) -> Result<()> {
+    let public_inputs = showdown_reveal_public_inputs(doubly_encrypted_deck_merkle_root, pocket_cards, player_key);
+    let proof = Groth16Proof::from_bytes(&proof.proof_data)?;
+    verify_groth16(vk, &proof, &public_inputs)
+}
+
+/// Hash arbitrary data (e.g. a ciphertext) into a single field element
+pub fn bytes_to_field_element(data: &[u8]) -> [u8; 32] {
+    let mut digest = hashv(&[b"zkpoker:bytes", data]).to_bytes();
+    digest[0] = 0;
+    digest
+}
+
+/// Encode a small integer (card or deck index) as a field element
+pub fn u8_to_field_element(value: u8) -> [u8; 32] {
+    let mut element = [0u8; 32];
+    element[31] = value;
+    element
+}
+
+/// Verify a proof stored in the hand against the current game state
+/// Used to settle disputes over optimistically accepted proofs; `vk` must be the key
+/// the game pinned for the proof's circuit.
+pub fn verify_stored_proof(game: &Game, stored: &StoredProof, vk: &Groth16VerifyingKey) -> Result<()> {
+    let proof = Groth16Proof::from_bytes(&stored.proof.proof_data)?;
+    let hand = &game.hand;
+    let [doubly_hi, doubly_lo] = commitment_to_field_elements(&hand.doubly_encrypted_deck_merkle_root);
+    let card_keys = card_keys_to_field_elements(game);
+    let submitter_key = *card_keys
+        .get(stored.submitter_index as usize)
+        .ok_or(GameError::InvalidPlayerIndex)?;
+    
+    match stored.proof_type {
+        ProofType::DeckCreation => {
+            let public_inputs = deck_creation_public_inputs(&hand.deck_merkle_root, &card_keys);
+            verify_groth16(vk, &proof, &public_inputs)
+        }
+        ProofType::Reshuffle => verify_reshuffle_proof(
+            vk,
+            &stored.proof,
+            &hand.deck_merkle_root,
+            &hand.doubly_encrypted_deck_merkle_root,
+            &card_keys,
+        ),
+        ProofType::CardDecryption { card_index } => {
+            // [doubly_hi, doubly_lo, card_index, H(decryption share), H(submitter key)]
+            let share = game
+                .find_revealed_card(card_index)
+                .ok_or(PokerError::MissingDecryptionShares)?;
+            let public_inputs: [[u8; 32]; CARD_DECRYPTION_PUBLIC_INPUTS] = [
+                doubly_hi,
+                doubly_lo,
+                u8_to_field_element(card_index),
+                bytes_to_field_element(&share.data),
+                submitter_key,
+            ];
+            verify_groth16(vk, &proof, &public_inputs)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_reshuffle_inputs_bind_both_decks_and_keys() {
+        let keys = [[1u8; 32], [2u8; 32]];
+        let inputs = reshuffle_public_inputs(&[0xaa; 32], &[0xbb; 32], &keys);
+        assert_eq!(inputs[..2], commitment_to_field_elements(&[0xaa; 32]));
+        assert_eq!(inputs[2..4], commitment_to_field_elements(&[0xbb; 32]));
+        assert_eq!(inputs[4..], keys);
+        
+        // Every input is below the field modulus
+        assert!(inputs[..4].iter().all(|input| input[..16] == [0u8; 16]));
+        
+        let malformed = ZkProof { proof_data: vec![0; 100] };
+        let ic = [[1u8; 64]; RESHUFFLE_PUBLIC_INPUTS + 1];
+        let vk = Groth16VerifyingKey {
+            alpha_g1: [1; 64],
+            beta_g2: [2; 128],
+            gamma_g2: [3; 128],
+            delta_g2: [4; 128],
+            ic: &ic,
+        };
+        assert!(verify_reshuffle_proof(&vk, &malformed, &[0xaa; 32], &[0xbb; 32], &keys).is_err());
+    }
+}
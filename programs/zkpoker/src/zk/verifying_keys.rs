@@ -0,0 +1,94 @@
+#[cfg(feature = "localnet")]
+use super::groth16::Groth16VerifyingKey;
+use crate::state::ProofCircuit;
+
+/// Number of public inputs of each circuit
+pub const DECK_CREATION_PUBLIC_INPUTS: usize = 4;
+pub const RESHUFFLE_PUBLIC_INPUTS: usize = 6;
+pub const CARD_DECRYPTION_PUBLIC_INPUTS: usize = 5;
+pub const SHOWDOWN_REVEAL_PUBLIC_INPUTS: usize = 5;
+
+/// BN254 G1 generator (1, 2)
+#[cfg(feature = "localnet")]
+const G1_GENERATOR: [u8; 64] = {
+    let mut point = [0u8; 64];
+    point[31] = 1;
+    point[63] = 2;
+    point
+};
+
+/// BN254 G2 generator in EIP-197 order (x_c1, x_c0, y_c1, y_c0)
+#[cfg(feature = "localnet")]
+const G2_GENERATOR: [u8; 128] = [
+    0x19, 0x8e, 0x93, 0x93, 0x92, 0x0d, 0x48, 0x3a, 0x72, 0x60, 0xbf, 0xb7, 0x31, 0xfb, 0x5d, 0x25,
+    0xf1, 0xaa, 0x49, 0x33, 0x35, 0xa9, 0xe7, 0x12, 0x97, 0xe4, 0x85, 0xb7, 0xae, 0xf3, 0x12, 0xc2,
+    0x18, 0x00, 0xde, 0xef, 0x12, 0x1f, 0x1e, 0x76, 0x42, 0x6a, 0x00, 0x66, 0x5e, 0x5c, 0x44, 0x79,
+    0x67, 0x43, 0x22, 0xd4, 0xf7, 0x5e, 0xda, 0xdd, 0x46, 0xde, 0xbd, 0x5c, 0xd9, 0x92, 0xf6, 0xed,
+    0x09, 0x06, 0x89, 0xd0, 0x58, 0x5f, 0xf0, 0x75, 0xec, 0x9e, 0x99, 0xad, 0x69, 0x0c, 0x33, 0x95,
+    0xbc, 0x4b, 0x31, 0x33, 0x70, 0xb3, 0x8e, 0xf3, 0x55, 0xac, 0xda, 0xdc, 0xd1, 0x22, 0x97, 0x5b,
+    0x12, 0xc8, 0x5e, 0xa5, 0xdb, 0x8c, 0x6d, 0xeb, 0x4a, 0xab, 0x71, 0x80, 0x8d, 0xcb, 0x40, 0x8f,
+    0xe3, 0xd1, 0xe7, 0x69, 0x0c, 0x43, 0xd3, 0x7b, 0x4c, 0xe6, 0xcc, 0x01, 0x66, 0xfa, 0x7d, 0xaa,
+];
+
+#[cfg(feature = "localnet")]
+const DECK_CREATION_IC: [[u8; 64]; DECK_CREATION_PUBLIC_INPUTS + 1] = [G1_GENERATOR; DECK_CREATION_PUBLIC_INPUTS + 1];
+#[cfg(feature = "localnet")]
+const RESHUFFLE_IC: [[u8; 64]; RESHUFFLE_PUBLIC_INPUTS + 1] = [G1_GENERATOR; RESHUFFLE_PUBLIC_INPUTS + 1];
+#[cfg(feature = "localnet")]
+const CARD_DECRYPTION_IC: [[u8; 64]; CARD_DECRYPTION_PUBLIC_INPUTS + 1] = [G1_GENERATOR; CARD_DECRYPTION_PUBLIC_INPUTS + 1];
+#[cfg(feature = "localnet")]
+const SHOWDOWN_REVEAL_IC: [[u8; 64]; SHOWDOWN_REVEAL_PUBLIC_INPUTS + 1] = [G1_GENERATOR; SHOWDOWN_REVEAL_PUBLIC_INPUTS + 1];
+
+/// ⚠️ DEVELOPMENT KEYS: every element is a curve generator, so these keys are NOT sound and
+/// anyone who knows them can forge proofs. They only fix the encoding and the number of public
+/// inputs, and are compiled in for localnet builds only; everywhere else a game must pin keys
+/// published to the registry.
+#[cfg(feature = "localnet")]
+const fn development_key(ic: &'static [[u8; 64]]) -> Groth16VerifyingKey<'static> {
+    Groth16VerifyingKey {
+        alpha_g1: G1_GENERATOR,
+        beta_g2: G2_GENERATOR,
+        gamma_g2: G2_GENERATOR,
+        delta_g2: G2_GENERATOR,
+        ic,
+    }
+}
+
+/// Verifying key for ProveCorrectDeckCreation
+#[cfg(feature = "localnet")]
+pub const DECK_CREATION_VERIFYING_KEY: Groth16VerifyingKey<'static> = development_key(&DECK_CREATION_IC);
+
+/// Verifying key for ProveCorrectReshuffle
+#[cfg(feature = "localnet")]
+pub const RESHUFFLE_VERIFYING_KEY: Groth16VerifyingKey<'static> = development_key(&RESHUFFLE_IC);
+
+/// Verifying key for ProveCorrectDecryption
+#[cfg(feature = "localnet")]
+pub const CARD_DECRYPTION_VERIFYING_KEY: Groth16VerifyingKey<'static> = development_key(&CARD_DECRYPTION_IC);
+
+/// Verifying key for ProveCorrectShowdownReveal
+#[cfg(feature = "localnet")]
+pub const SHOWDOWN_REVEAL_VERIFYING_KEY: Groth16VerifyingKey<'static> = development_key(&SHOWDOWN_REVEAL_IC);
+
+impl ProofCircuit {
+    /// Number of public inputs the circuit's proofs have
+    pub fn public_inputs(self) -> usize {
+        match self {
+            ProofCircuit::DeckCreation => DECK_CREATION_PUBLIC_INPUTS,
+            ProofCircuit::Reshuffle => RESHUFFLE_PUBLIC_INPUTS,
+            ProofCircuit::CardDecryption => CARD_DECRYPTION_PUBLIC_INPUTS,
+            ProofCircuit::ShowdownReveal => SHOWDOWN_REVEAL_PUBLIC_INPUTS,
+        }
+    }
+    
+    /// Development key used by games pinned to version 0 on localnet
+    #[cfg(feature = "localnet")]
+    pub fn builtin_verifying_key(self) -> Groth16VerifyingKey<'static> {
+        match self {
+            ProofCircuit::DeckCreation => DECK_CREATION_VERIFYING_KEY,
+            ProofCircuit::Reshuffle => RESHUFFLE_VERIFYING_KEY,
+            ProofCircuit::CardDecryption => CARD_DECRYPTION_VERIFYING_KEY,
+            ProofCircuit::ShowdownReveal => SHOWDOWN_REVEAL_VERIFYING_KEY,
+        }
+    }
+}